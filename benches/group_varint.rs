@@ -0,0 +1,80 @@
+//! Compares Group Varint decoding against decoding the same values one LEB128
+//! varint at a time.
+//!
+//! Run with `cargo bench --bench group_varint`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use uvarint::{decode_u32, encode_u32_into, group_varint};
+
+const COUNT: usize = 1 << 20;
+const ROUNDS: u32 = 20;
+
+/// Deterministic values with a mix of 1- to 4-byte magnitudes.
+fn values() -> Vec<u32> {
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    (0..COUNT)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            let bits = (state >> 59) as u32 + 1;
+            ((state >> 32) as u32) >> (32 - bits)
+        })
+        .collect()
+}
+
+fn time(name: &str, bytes: usize, mut f: impl FnMut() -> u64) {
+    let mut best = Duration::MAX;
+    let mut checksum = 0;
+
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        checksum = black_box(f());
+        best = best.min(start.elapsed());
+    }
+
+    println!(
+        "{name:<16} {bytes:>9} bytes  {:>6.2} ns/value  (checksum {checksum})",
+        best.as_nanos() as f64 / COUNT as f64
+    );
+}
+
+fn main() {
+    let values = values();
+
+    let mut leb128 = vec![0u8; COUNT * 5];
+    let mut pos = 0;
+    for &value in &values {
+        pos += encode_u32_into(value, &mut leb128[pos..]).unwrap();
+    }
+    leb128.truncate(pos);
+
+    let group = group_varint::encode_u32_group(&values);
+    let mut out = vec![0u32; COUNT];
+
+    time("leb128", leb128.len(), || {
+        let mut data = black_box(&leb128[..]);
+        let mut sum = 0u64;
+        for slot in out.iter_mut() {
+            let (n, value) = decode_u32(data).unwrap();
+            *slot = value;
+            sum += value as u64;
+            data = &data[n..];
+        }
+        sum
+    });
+
+    time("group_varint", group.len(), || {
+        group_varint::decode_u32_group(black_box(&group), &mut out).unwrap();
+        out.iter().map(|&v| v as u64).sum()
+    });
+
+    time("group_varint it", group.len(), || {
+        group_varint::iter_u32_group(black_box(&group), COUNT)
+            .map(|v| v.unwrap() as u64)
+            .sum()
+    });
+}
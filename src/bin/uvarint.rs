@@ -0,0 +1,276 @@
+//! Command-line tool for encoding, decoding and inspecting LEB128 varints.
+//!
+//! Built with the `cli` feature:
+//!
+//! ```text
+//! $ uvarint encode 1 300
+//! 01
+//! ac 02
+//! $ uvarint decode "01 ac 02"
+//! 1                    1 byte
+//! 300                  2 bytes
+//! $ uvarint dump values.bin
+//!   offset  value                 raw
+//!        0  1                     01
+//!        1  300                   ac 02
+//! ```
+
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand};
+use uvarint::{UVarintError, encode_u64, iter_u64};
+
+/// Encode, decode and inspect LEB128 varints
+#[derive(Parser)]
+#[command(name = "uvarint", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the hex encoding of each number
+    Encode {
+        #[arg(required = true)]
+        values: Vec<u64>,
+    },
+    /// Decode concatenated varints from hex, printing each value and its length
+    Decode {
+        /// Hex bytes; whitespace and a leading `0x` are ignored
+        #[arg(required = true)]
+        hex: Vec<String>,
+    },
+    /// Walk a file of concatenated varints, printing offset, value and raw bytes
+    Dump {
+        /// File to read, or `-` for stdin
+        file: PathBuf,
+    },
+}
+
+/// A decoded varint and where it sits in the input.
+struct Entry<'a> {
+    offset: usize,
+    value: u64,
+    raw: &'a [u8],
+}
+
+/// A decode failure and the offset of the varint that caused it.
+#[derive(Debug)]
+struct DecodeFailure {
+    offset: usize,
+    error: UVarintError,
+}
+
+/// Decodes every varint in `data`, stopping at the first failure.
+fn walk(data: &[u8]) -> (Vec<Entry<'_>>, Option<DecodeFailure>) {
+    let mut entries = Vec::new();
+    let mut iter = iter_u64(data);
+
+    loop {
+        let rest = iter.remaining();
+        let offset = data.len() - rest.len();
+
+        match iter.next() {
+            None => return (entries, None),
+            Some(Ok(value)) => {
+                let len = rest.len() - iter.remaining().len();
+                entries.push(Entry {
+                    offset,
+                    value,
+                    raw: &rest[..len],
+                });
+            }
+            Some(Err(error)) => {
+                // Ten continuation bytes cannot end a u64, however much follows
+                let error = match error {
+                    UVarintError::Incomplete if rest.len() >= 10 => UVarintError::Overflow,
+                    error => error,
+                };
+                return (entries, Some(DecodeFailure { offset, error }));
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 3);
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+        write!(result, "{byte:02x}").expect("writing to a String cannot fail");
+    }
+    result
+}
+
+fn parse_hex(args: &[String]) -> Result<Vec<u8>, String> {
+    let mut digits = String::new();
+    for arg in args {
+        let arg = arg.trim();
+        let arg = arg
+            .strip_prefix("0x")
+            .or_else(|| arg.strip_prefix("0X"))
+            .unwrap_or(arg);
+        digits.extend(arg.chars().filter(|c| !c.is_whitespace()));
+    }
+
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex digit `{c}`"));
+    }
+
+    if !digits.len().is_multiple_of(2) {
+        return Err("hex input has an odd number of digits".to_string());
+    }
+
+    // Only ASCII hex digits are left, so every pair is a char boundary
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex byte `{}`", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+fn read_input(file: &PathBuf) -> io::Result<Vec<u8>> {
+    if file.as_os_str() == "-" {
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data)?;
+        return Ok(data);
+    }
+
+    fs::read(file)
+}
+
+fn report(failure: Option<DecodeFailure>) -> ExitCode {
+    match failure {
+        None => ExitCode::SUCCESS,
+        Some(DecodeFailure { offset, error }) => {
+            eprintln!("error at offset {offset}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Encode { values } => {
+            for value in values {
+                println!("{}", to_hex(&encode_u64(value)));
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Decode { hex } => {
+            let data = match parse_hex(&hex) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let (entries, failure) = walk(&data);
+            for entry in entries {
+                let unit = if entry.raw.len() == 1 {
+                    "byte"
+                } else {
+                    "bytes"
+                };
+                println!("{:<20} {} {unit}", entry.value, entry.raw.len());
+            }
+            report(failure)
+        }
+        Command::Dump { file } => {
+            let data = match read_input(&file) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("error: {}: {e}", file.display());
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let (entries, failure) = walk(&data);
+            println!("{:>8}  {:<20}  raw", "offset", "value");
+            for entry in entries {
+                println!(
+                    "{:>8}  {:<20}  {}",
+                    entry.offset,
+                    entry.value,
+                    to_hex(entry.raw)
+                );
+            }
+            report(failure)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_offsets() {
+        let (entries, failure) = walk(&[0x01, 0xAC, 0x02, 0x7F]);
+        assert!(failure.is_none());
+
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.offset, e.value, e.raw.to_vec()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, 1, vec![0x01]),
+                (1, 300, vec![0xAC, 0x02]),
+                (3, 127, vec![0x7F])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_reports_failing_offset() {
+        let (entries, failure) = walk(&[0x01, 0x05, 0xAC]);
+        let failure = failure.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(failure.offset, 2);
+        assert!(matches!(failure.error, UVarintError::Incomplete));
+
+        let mut data = vec![0x00];
+        data.extend([0xFF; 10]);
+        data.push(0x01);
+        let failure = walk(&data).1.unwrap();
+        assert_eq!(failure.offset, 1);
+        assert!(matches!(failure.error, UVarintError::Overflow));
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        let args = vec!["0xac 02".to_string(), "7F".to_string()];
+        assert_eq!(parse_hex(&args).unwrap(), vec![0xAC, 0x02, 0x7F]);
+
+        assert!(parse_hex(&["abc".to_string()]).is_err());
+        assert!(parse_hex(&["zz".to_string()]).is_err());
+
+        // Non-ASCII input is rejected rather than sliced mid-character
+        assert_eq!(
+            parse_hex(&["aé1".to_string()]).unwrap_err(),
+            "invalid hex digit `é`"
+        );
+        assert!(parse_hex(&["é".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(&[0xAC, 0x02]), "ac 02");
+        assert_eq!(to_hex(&[]), "");
+    }
+}
@@ -0,0 +1,479 @@
+//! Blocks of integers stored as either LEB128 varints or frame-of-reference
+//! bit-packing, whichever is smaller.
+//!
+//! # Encoding Format
+//!
+//! When the values in a block have similar magnitudes, the continuation bit in
+//! every varint byte is wasted. Frame-of-reference encoding subtracts the
+//! block minimum and packs each residual into exactly as many bits as the
+//! largest one needs:
+//!
+//! ```text
+//! [encoding: u8] [count: varint] ...
+//!
+//! encoding 0 (varint): count LEB128 varints
+//! encoding 1 (packed): [min: varint] [width: u8] [ceil(count * width / 8) bytes]
+//! ```
+//!
+//! Residuals are packed little-endian: value `i` occupies bits
+//! `i * width .. (i + 1) * width` of the packed bytes, starting from the least
+//! significant bit of the first byte.
+//!
+//! [`encode_block_u64`] computes the size of both encodings up front (using
+//! [`crate::encoded_len_u64`] for the varint side) and writes the smaller one,
+//! so callers never need to pick.
+//!
+//! # Examples
+//!
+//! ```
+//! use uvarint::block::{BlockEncoding, choose_encoding_u64, decode_block_u64, encode_block_u64};
+//!
+//! // Timestamps in milliseconds: large, but close together
+//! let values: Vec<u64> = (0..128).map(|i| 1_700_000_000_000 + i * 7).collect();
+//! assert_eq!(choose_encoding_u64(&values), BlockEncoding::Packed);
+//!
+//! let encoded = encode_block_u64(&values);
+//! assert!(encoded.len() < values.len() * 6);
+//! assert_eq!(decode_block_u64(&encoded).unwrap(), (encoded.len(), values));
+//! ```
+
+use crate::{
+    decode::decode_u64,
+    encode::{encode_u64_into, encoded_len_u64},
+    error::UVarintError,
+};
+
+/// Largest block [`decode_block_u64`] accepts. Bit-packed blocks of equal
+/// values take no bytes per value, so the count alone cannot be trusted.
+pub const DEFAULT_MAX_BLOCK_LEN: usize = 1 << 24;
+
+/// How a block's values are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockEncoding {
+    /// One LEB128 varint per value.
+    Varint = 0,
+    /// Minimum and bit width header, followed by bit-packed residuals.
+    Packed = 1,
+}
+
+impl TryFrom<u8> for BlockEncoding {
+    type Error = UVarintError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(BlockEncoding::Varint),
+            1 => Ok(BlockEncoding::Packed),
+            _ => Err(UVarintError::InvalidBlockEncoding(value)),
+        }
+    }
+}
+
+/// Minimum value and residual bit width of a block.
+fn frame(values: &[u64]) -> (u64, u32) {
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    (min, u64::BITS - (max - min).leading_zeros())
+}
+
+fn varint_len(values: &[u64]) -> usize {
+    values.iter().map(|&v| encoded_len_u64(v)).sum()
+}
+
+fn packed_len(values: &[u64], min: u64, width: u32) -> usize {
+    encoded_len_u64(min) + 1 + (values.len() * width as usize).div_ceil(8)
+}
+
+fn choose(values: &[u64], min: u64, width: u32) -> BlockEncoding {
+    if packed_len(values, min, width) < varint_len(values) {
+        BlockEncoding::Packed
+    } else {
+        BlockEncoding::Varint
+    }
+}
+
+/// Returns the encoding [`encode_block_u64`] would pick for `values`.
+///
+/// Ties go to [`BlockEncoding::Varint`], which is cheaper to decode.
+pub fn choose_encoding_u64(values: &[u64]) -> BlockEncoding {
+    let (min, width) = frame(values);
+    choose(values, min, width)
+}
+
+/// Returns the number of bytes [`encode_block_u64`] would produce for `values`.
+pub fn encoded_len_block_u64(values: &[u64]) -> usize {
+    let (min, width) = frame(values);
+    let body = packed_len(values, min, width).min(varint_len(values));
+
+    1 + encoded_len_u64(values.len() as u64) + body
+}
+
+/// Encodes a block of u64 values, choosing the smaller of plain LEB128 and
+/// frame-of-reference bit-packing.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::block::encode_block_u64;
+///
+/// // Small values: plain varints win
+/// assert_eq!(encode_block_u64(&[1, 2, 3]), vec![0x00, 0x03, 0x01, 0x02, 0x03]);
+///
+/// // min = 1000, residuals 0..=3 packed 2 bits each: 0b11_10_01_00
+/// assert_eq!(
+///     encode_block_u64(&[1000, 1001, 1002, 1003]),
+///     vec![0x01, 0x04, 0xE8, 0x07, 0x02, 0xE4]
+/// );
+/// ```
+pub fn encode_block_u64(values: &[u64]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(encoded_len_block_u64(values));
+    let mut buf = [0u8; 10];
+
+    let (min, width) = frame(values);
+    let encoding = choose(values, min, width);
+
+    result.push(encoding as u8);
+    let n = encode_u64_into(values.len() as u64, &mut buf).expect("10 bytes fit any u64");
+    result.extend_from_slice(&buf[..n]);
+
+    match encoding {
+        BlockEncoding::Varint => {
+            for &value in values {
+                let n = encode_u64_into(value, &mut buf).expect("10 bytes fit any u64");
+                result.extend_from_slice(&buf[..n]);
+            }
+        }
+        BlockEncoding::Packed => {
+            let n = encode_u64_into(min, &mut buf).expect("10 bytes fit any u64");
+            result.extend_from_slice(&buf[..n]);
+            result.push(width as u8);
+            pack(values.iter().map(|&v| v - min), width, &mut result);
+        }
+    }
+
+    result
+}
+
+/// Appends `width`-bit residuals to `out`, least significant bit first.
+fn pack(residuals: impl Iterator<Item = u64>, width: u32, out: &mut Vec<u8>) {
+    if width == 0 {
+        return;
+    }
+
+    let mut acc: u128 = 0;
+    let mut bits = 0;
+
+    for residual in residuals {
+        acc |= (residual as u128) << bits;
+        bits += width;
+
+        while bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            bits -= 8;
+        }
+    }
+
+    if bits > 0 {
+        out.push(acc as u8);
+    }
+}
+
+/// Decodes a block produced by [`encode_block_u64`], returning the bytes
+/// consumed and the values.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::block::decode_block_u64;
+///
+/// let data = [0x01, 0x04, 0xE8, 0x07, 0x02, 0xE4];
+/// assert_eq!(
+///     decode_block_u64(&data).unwrap(),
+///     (6, vec![1000, 1001, 1002, 1003])
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the block does,
+/// `UVarintError::InvalidBlockEncoding` for an unknown encoding byte,
+/// `UVarintError::BlockTooLarge` if the block holds more than
+/// [`DEFAULT_MAX_BLOCK_LEN`] values, or `UVarintError::Overflow` if a value
+/// or the bit width does not fit in a u64.
+pub fn decode_block_u64(data: &[u8]) -> Result<(usize, Vec<u64>), UVarintError> {
+    decode_block_u64_with_max(data, DEFAULT_MAX_BLOCK_LEN)
+}
+
+/// Like [`decode_block_u64`], rejecting blocks of more than `max_count`
+/// values before anything is allocated.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::{UVarintError, block::{decode_block_u64_with_max, encode_block_u64}};
+///
+/// let encoded = encode_block_u64(&[7; 100]);
+/// assert!(decode_block_u64_with_max(&encoded, 100).is_ok());
+/// assert!(matches!(
+///     decode_block_u64_with_max(&encoded, 99),
+///     Err(UVarintError::BlockTooLarge { count: 100, max: 99 })
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// As [`decode_block_u64`], with `max_count` in place of the default limit.
+pub fn decode_block_u64_with_max(
+    data: &[u8],
+    max_count: usize,
+) -> Result<(usize, Vec<u64>), UVarintError> {
+    let &tag = data.first().ok_or(UVarintError::Incomplete)?;
+    let encoding = BlockEncoding::try_from(tag)?;
+    let mut pos = 1;
+
+    let (n, count) = decode_u64(&data[pos..])?;
+    let count = match usize::try_from(count) {
+        Ok(count) if count <= max_count => count,
+        _ => {
+            return Err(UVarintError::BlockTooLarge {
+                count,
+                max: max_count,
+            });
+        }
+    };
+    pos += n;
+
+    match encoding {
+        BlockEncoding::Varint => {
+            // Every varint is at least one byte
+            if count > data.len() - pos {
+                return Err(UVarintError::Incomplete);
+            }
+
+            let mut values = Vec::with_capacity(count);
+            for _ in 0..count {
+                let (n, value) = decode_u64(&data[pos..])?;
+                values.push(value);
+                pos += n;
+            }
+
+            Ok((pos, values))
+        }
+        BlockEncoding::Packed => {
+            let (n, min) = decode_u64(&data[pos..])?;
+            pos += n;
+
+            let &width = data.get(pos).ok_or(UVarintError::Incomplete)?;
+            let width = width as u32;
+            pos += 1;
+
+            if width > u64::BITS {
+                return Err(UVarintError::Overflow);
+            }
+
+            let packed_len = count
+                .checked_mul(width as usize)
+                .ok_or(UVarintError::Overflow)?
+                .div_ceil(8);
+            let packed = data
+                .get(pos..pos + packed_len)
+                .ok_or(UVarintError::Incomplete)?;
+            pos += packed_len;
+
+            let mask = u64::MAX >> (u64::BITS - width.max(1));
+            // Zero-width blocks have no bytes to bound `count` by
+            let mut values = Vec::with_capacity(count.min(data.len() - pos));
+            let mut acc: u128 = 0;
+            let mut bits = 0;
+            let mut bytes = packed.iter();
+
+            for _ in 0..count {
+                while bits < width {
+                    acc |= (*bytes.next().expect("length checked above") as u128) << bits;
+                    bits += 8;
+                }
+
+                let residual = if width == 0 { 0 } else { acc as u64 & mask };
+                acc >>= width;
+                bits -= width;
+
+                values.push(min.checked_add(residual).ok_or(UVarintError::Overflow)?);
+            }
+
+            Ok((pos, values))
+        }
+    }
+}
+
+/// Encodes a block of u32 values; see [`encode_block_u64`].
+pub fn encode_block_u32(values: &[u32]) -> Vec<u8> {
+    let widened: Vec<u64> = values.iter().map(|&v| v as u64).collect();
+    encode_block_u64(&widened)
+}
+
+/// Decodes a block of u32 values; see [`decode_block_u64`].
+///
+/// # Errors
+///
+/// As [`decode_block_u64`], plus `UVarintError::Overflow` if a value does not
+/// fit in a u32.
+pub fn decode_block_u32(data: &[u8]) -> Result<(usize, Vec<u32>), UVarintError> {
+    decode_block_u32_with_max(data, DEFAULT_MAX_BLOCK_LEN)
+}
+
+/// Decodes a block of u32 values; see [`decode_block_u64_with_max`].
+///
+/// # Errors
+///
+/// As [`decode_block_u64_with_max`], plus `UVarintError::Overflow` if a value
+/// does not fit in a u32.
+pub fn decode_block_u32_with_max(
+    data: &[u8],
+    max_count: usize,
+) -> Result<(usize, Vec<u32>), UVarintError> {
+    let (n, values) = decode_block_u64_with_max(data, max_count)?;
+    let values = values
+        .into_iter()
+        .map(|v| u32::try_from(v).map_err(|_| UVarintError::Overflow))
+        .collect::<Result<_, _>>()?;
+    Ok((n, values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(values: &[u64]) -> Vec<u8> {
+        let encoded = encode_block_u64(values);
+        assert_eq!(encoded.len(), encoded_len_block_u64(values));
+        assert_eq!(
+            decode_block_u64(&encoded).unwrap(),
+            (encoded.len(), values.to_vec())
+        );
+        encoded
+    }
+
+    #[test]
+    fn test_empty_block() {
+        assert_eq!(roundtrip(&[]), vec![0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_small_values_use_varint() {
+        let values = [0, 1, 127, 3, 100];
+        assert_eq!(choose_encoding_u64(&values), BlockEncoding::Varint);
+        assert_eq!(roundtrip(&values)[0], BlockEncoding::Varint as u8);
+    }
+
+    #[test]
+    fn test_clustered_values_use_packed() {
+        let values: Vec<u64> = (0..100).map(|i| (1 << 40) + (i * 37) % 1000).collect();
+        assert_eq!(choose_encoding_u64(&values), BlockEncoding::Packed);
+
+        let encoded = roundtrip(&values);
+        let varint: usize = values.iter().map(|&v| encoded_len_u64(v)).sum();
+        assert!(encoded.len() < varint / 2);
+    }
+
+    #[test]
+    fn test_constant_block_has_zero_width() {
+        let values = [u64::MAX; 50];
+        let encoded = roundtrip(&values);
+
+        // tag, count, 10-byte min, width 0, no residuals
+        assert_eq!(encoded.len(), 1 + 1 + 10 + 1);
+        assert_eq!(encoded[12], 0);
+    }
+
+    #[test]
+    fn test_full_width_residuals() {
+        let values = [0, u64::MAX, 1 << 63, 12_345, u64::MAX - 1];
+        roundtrip(&values);
+    }
+
+    #[test]
+    fn test_every_width() {
+        for width in 0..=64u32 {
+            let span = if width == 0 {
+                0
+            } else {
+                u64::MAX >> (64 - width)
+            };
+            let values: Vec<u64> = (0..17)
+                .map(|i| (span / 16 * i).saturating_add(1_000))
+                .collect();
+            let (n, decoded) = decode_block_u64(&encode_block_u64(&values)).unwrap();
+            assert!(n > 0);
+            assert_eq!(decoded, values);
+        }
+    }
+
+    #[test]
+    fn test_u32_block() {
+        let values: Vec<u32> = (0..64).map(|i| 3_000_000_000 + i).collect();
+        let encoded = encode_block_u32(&values);
+        assert_eq!(decode_block_u32(&encoded).unwrap(), (encoded.len(), values));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(
+            decode_block_u64(&[]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_block_u64(&[0x07, 0x00]),
+            Err(UVarintError::InvalidBlockEncoding(7))
+        ));
+        assert!(matches!(
+            decode_block_u64(&[0x00, 0x05, 0x01]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_block_u64(&[0x01, 0x01, 0x00, 65, 0x00]),
+            Err(UVarintError::Overflow)
+        ));
+
+        let encoded = encode_block_u64(&[1000, 1001, 1002, 1003]);
+        assert!(matches!(
+            decode_block_u64(&encoded[..encoded.len() - 1]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_decode_zero_width_count_is_bounded() {
+        // Packed, count = 2^63 - 1, min = 0, width 0: no packed bytes at all
+        let hostile = [
+            0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x00,
+        ];
+        assert!(matches!(
+            decode_block_u64(&hostile),
+            Err(UVarintError::BlockTooLarge { .. })
+        ));
+
+        // count = 2^32 fits a usize but must not be allocated up front
+        let mut huge = vec![0x01];
+        huge.extend(crate::encode_u64(1 << 32));
+        huge.extend([0x00, 0x00]);
+        assert!(matches!(
+            decode_block_u64(&huge),
+            Err(UVarintError::BlockTooLarge {
+                count: 0x1_0000_0000,
+                ..
+            })
+        ));
+
+        // Legitimate zero-width blocks still decode
+        let encoded = encode_block_u64(&[42; 1000]);
+        assert_eq!(encoded.len(), 5);
+        assert_eq!(decode_block_u64(&encoded).unwrap(), (5, vec![42; 1000]));
+        assert!(matches!(
+            decode_block_u32_with_max(&encoded, 999),
+            Err(UVarintError::BlockTooLarge {
+                count: 1000,
+                max: 999
+            })
+        ));
+    }
+}
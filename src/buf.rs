@@ -0,0 +1,214 @@
+//! Varint extension traits for `bytes::Buf` and `bytes::BufMut`.
+//!
+//! Enabled with the `bytes` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::BytesMut;
+//! use uvarint::buf::{BufMutVarintExt, BufVarintExt};
+//!
+//! let mut buf = BytesMut::new();
+//! buf.put_varint_u64(300);
+//! buf.put_varint_i32(-2);
+//!
+//! assert_eq!(&buf[..], &[0xAC, 0x02, 0x03]);
+//! assert_eq!(buf.get_varint_u64().unwrap(), 300);
+//! assert_eq!(buf.get_varint_i32().unwrap(), -2);
+//! ```
+
+use bytes::{Buf, BufMut};
+
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    encode::{encode_u32_into, encode_u64_into, encode_u128_into},
+    error::UVarintError,
+    iter::DecodeFn,
+    zigzag::{
+        zigzag_decode_i32, zigzag_decode_i64, zigzag_decode_i128, zigzag_encode_i32,
+        zigzag_encode_i64, zigzag_encode_i128,
+    },
+};
+
+/// Longest encoding of any supported width (a u128).
+const MAX_LEN: usize = 19;
+
+/// Decodes one varint from `buf`, advancing past it on success.
+///
+/// The common case decodes straight out of the current chunk. Only a varint
+/// that straddles a chunk boundary is gathered byte by byte into a small stack
+/// buffer.
+fn get_varint<B: Buf + ?Sized, T>(
+    buf: &mut B,
+    decode: DecodeFn<T>,
+    max_len: usize,
+) -> Result<T, UVarintError> {
+    let chunk = buf.chunk();
+    let straddles = chunk.len() < max_len && buf.remaining() > chunk.len();
+
+    match decode(chunk) {
+        Ok((n, value)) => {
+            buf.advance(n);
+            return Ok(value);
+        }
+        Err(UVarintError::Incomplete) if straddles => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut scratch = [0u8; MAX_LEN];
+    let mut len = 0;
+    while len < max_len && buf.has_remaining() {
+        let byte = buf.get_u8();
+        scratch[len] = byte;
+        len += 1;
+
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    decode(&scratch[..len]).map(|(_, value)| value)
+}
+
+/// Extension trait for reading varints from `Buf` types
+///
+/// # Errors
+///
+/// Every method returns `UVarintError::Incomplete` if the buffer ends
+/// mid-varint, or `UVarintError::Overflow` if the value does not fit the
+/// requested width. A varint contained in one chunk leaves the buffer
+/// untouched on error; one that straddles chunks is consumed as far as it was
+/// read.
+pub trait BufVarintExt: Buf {
+    fn get_varint_u32(&mut self) -> Result<u32, UVarintError> {
+        get_varint(self, decode_u32, 5)
+    }
+
+    fn get_varint_u64(&mut self) -> Result<u64, UVarintError> {
+        get_varint(self, decode_u64, 10)
+    }
+
+    fn get_varint_u128(&mut self) -> Result<u128, UVarintError> {
+        get_varint(self, decode_u128, 19)
+    }
+
+    fn get_varint_i32(&mut self) -> Result<i32, UVarintError> {
+        self.get_varint_u32().map(zigzag_decode_i32)
+    }
+
+    fn get_varint_i64(&mut self) -> Result<i64, UVarintError> {
+        self.get_varint_u64().map(zigzag_decode_i64)
+    }
+
+    fn get_varint_i128(&mut self) -> Result<i128, UVarintError> {
+        self.get_varint_u128().map(zigzag_decode_i128)
+    }
+}
+
+impl<B: Buf + ?Sized> BufVarintExt for B {}
+
+/// Extension trait for writing varints to `BufMut` types
+///
+/// Like `BufMut::put_u8`, these panic if the buffer cannot grow to fit the
+/// encoded value.
+pub trait BufMutVarintExt: BufMut {
+    fn put_varint_u32(&mut self, value: u32) {
+        let mut scratch = [0u8; 5];
+        let n = encode_u32_into(value, &mut scratch).expect("5 bytes fit any u32 varint");
+        self.put_slice(&scratch[..n]);
+    }
+
+    fn put_varint_u64(&mut self, value: u64) {
+        let mut scratch = [0u8; 10];
+        let n = encode_u64_into(value, &mut scratch).expect("10 bytes fit any u64 varint");
+        self.put_slice(&scratch[..n]);
+    }
+
+    fn put_varint_u128(&mut self, value: u128) {
+        let mut scratch = [0u8; MAX_LEN];
+        let n = encode_u128_into(value, &mut scratch).expect("19 bytes fit any u128 varint");
+        self.put_slice(&scratch[..n]);
+    }
+
+    fn put_varint_i32(&mut self, value: i32) {
+        self.put_varint_u32(zigzag_encode_i32(value));
+    }
+
+    fn put_varint_i64(&mut self, value: i64) {
+        self.put_varint_u64(zigzag_encode_i64(value));
+    }
+
+    fn put_varint_i128(&mut self, value: i128) {
+        self.put_varint_u128(zigzag_encode_i128(value));
+    }
+}
+
+impl<B: BufMut + ?Sized> BufMutVarintExt for B {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::{Bytes, BytesMut};
+
+    #[test]
+    fn test_roundtrip_all_widths() {
+        let mut buf = BytesMut::new();
+        buf.put_varint_u32(u32::MAX);
+        buf.put_varint_u64(u64::MAX);
+        buf.put_varint_u128(u128::MAX);
+        buf.put_varint_i32(i32::MIN);
+        buf.put_varint_i64(-1);
+        buf.put_varint_i128(i128::MIN);
+
+        let mut buf = buf.freeze();
+        assert_eq!(buf.get_varint_u32().unwrap(), u32::MAX);
+        assert_eq!(buf.get_varint_u64().unwrap(), u64::MAX);
+        assert_eq!(buf.get_varint_u128().unwrap(), u128::MAX);
+        assert_eq!(buf.get_varint_i32().unwrap(), i32::MIN);
+        assert_eq!(buf.get_varint_i64().unwrap(), -1);
+        assert_eq!(buf.get_varint_i128().unwrap(), i128::MIN);
+        assert!(!buf.has_remaining());
+    }
+
+    #[test]
+    fn test_get_across_chunks() {
+        // 2^63 takes 10 bytes; split it at every possible boundary
+        let encoded = crate::encode_u64(1 << 63);
+
+        for split in 1..encoded.len() {
+            let head = Bytes::copy_from_slice(&encoded[..split]);
+            let tail = Bytes::copy_from_slice(&encoded[split..]);
+            let mut buf = head.chain(tail).chain(&[0x05u8][..]);
+
+            assert_eq!(buf.get_varint_u64().unwrap(), 1 << 63);
+            assert_eq!(buf.get_varint_u64().unwrap(), 5);
+            assert!(!buf.has_remaining());
+        }
+    }
+
+    #[test]
+    fn test_get_incomplete_leaves_contiguous_buf() {
+        let mut buf = Bytes::from_static(&[0xAC]);
+        assert!(matches!(
+            buf.get_varint_u64(),
+            Err(UVarintError::Incomplete)
+        ));
+        assert_eq!(buf.len(), 1);
+
+        let mut split = (&[0xAC][..]).chain(&[0x80][..]);
+        assert!(matches!(
+            split.get_varint_u64(),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_put_into_slice() {
+        let mut storage = [0u8; 4];
+        let mut buf = &mut storage[..];
+        buf.put_varint_u64(300);
+        buf.put_varint_i64(-64);
+
+        assert_eq!(storage, [0xAC, 0x02, 0x7F, 0x00]);
+    }
+}
@@ -0,0 +1,218 @@
+//! `tokio_util::codec` support for varint-length-delimited frames.
+//!
+//! Enabled with the `tokio-util` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::{Bytes, BytesMut};
+//! use tokio_util::codec::{Decoder, Encoder};
+//! use uvarint::codec::VarintLengthCodec;
+//!
+//! let mut codec = VarintLengthCodec::new();
+//! let mut buf = BytesMut::new();
+//!
+//! codec.encode(Bytes::from_static(b"hello"), &mut buf).unwrap();
+//! assert_eq!(&buf[..], b"\x05hello");
+//!
+//! let frame = codec.decode(&mut buf).unwrap().unwrap();
+//! assert_eq!(&frame[..], b"hello");
+//! ```
+
+use std::io;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{
+    decode::decode_u64, encode::encode_u64_into, error::UVarintError, frame::checked_frame_len,
+};
+
+/// Default maximum frame length, matching `tokio_util`'s
+/// `LengthDelimitedCodec`.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// Maximum encoded length of a u64 varint.
+const MAX_PREFIX_LEN: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+enum DecodeState {
+    /// Waiting for a complete length prefix.
+    Head,
+    /// Prefix consumed; waiting for this many payload bytes.
+    Data(usize),
+}
+
+/// Codec for frames prefixed with their LEB128-encoded length.
+///
+/// The decoder consumes the length prefix as soon as it is complete, then
+/// waits until the whole payload is buffered before yielding it. A prefix
+/// split across reads is simply left in the buffer until more bytes arrive.
+#[derive(Debug, Clone)]
+pub struct VarintLengthCodec {
+    max_frame_len: usize,
+    state: DecodeState,
+}
+
+impl VarintLengthCodec {
+    /// Creates a codec that accepts frames up to [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn new() -> Self {
+        Self::with_max_frame_len(DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a codec that rejects frames longer than `max_frame_len` bytes.
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        VarintLengthCodec {
+            max_frame_len,
+            state: DecodeState::Head,
+        }
+    }
+
+    /// Returns the maximum frame length this codec accepts.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    fn decode_head(&mut self, src: &mut BytesMut) -> Result<Option<usize>, UVarintError> {
+        match decode_u64(src) {
+            Ok((n, len)) => {
+                let len = checked_frame_len(len, self.max_frame_len)?;
+                src.advance(n);
+                src.reserve(len);
+                Ok(Some(len))
+            }
+            // Ten bytes all carrying a continuation bit can never terminate
+            Err(UVarintError::Incomplete) if src.len() >= MAX_PREFIX_LEN => {
+                Err(UVarintError::Overflow)
+            }
+            Err(UVarintError::Incomplete) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Default for VarintLengthCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for VarintLengthCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, io::Error> {
+        let len = match self.state {
+            DecodeState::Head => match self.decode_head(src)? {
+                Some(len) => {
+                    self.state = DecodeState::Data(len);
+                    len
+                }
+                None => return Ok(None),
+            },
+            DecodeState::Data(len) => len,
+        };
+
+        if src.len() < len {
+            return Ok(None);
+        }
+
+        self.state = DecodeState::Head;
+        Ok(Some(src.split_to(len)))
+    }
+}
+
+impl Encoder<Bytes> for VarintLengthCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), io::Error> {
+        let len = checked_frame_len(item.len() as u64, self.max_frame_len)?;
+
+        let mut prefix = [0u8; MAX_PREFIX_LEN];
+        let n = encode_u64_into(len as u64, &mut prefix)?;
+
+        dst.reserve(n + len);
+        dst.put_slice(&prefix[..n]);
+        dst.put(item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(payload: &[u8]) -> BytesMut {
+        let mut buf = BytesMut::new();
+        VarintLengthCodec::new()
+            .encode(Bytes::copy_from_slice(payload), &mut buf)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_decode_multiple_frames() {
+        let mut buf = frame(b"one");
+        buf.extend_from_slice(&frame(b""));
+        buf.extend_from_slice(&frame(&[9u8; 300]));
+
+        let mut codec = VarintLengthCodec::new();
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], b"one");
+        assert!(codec.decode(&mut buf).unwrap().unwrap().is_empty());
+        assert_eq!(&codec.decode(&mut buf).unwrap().unwrap()[..], &[9u8; 300]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_decode_byte_at_a_time() {
+        // 300-byte payload needs a 2-byte prefix, which arrives split
+        let encoded = frame(&[1u8; 300]);
+        let mut codec = VarintLengthCodec::new();
+        let mut buf = BytesMut::new();
+
+        for (i, &byte) in encoded.iter().enumerate() {
+            buf.put_u8(byte);
+            let decoded = codec.decode(&mut buf).unwrap();
+
+            if i + 1 < encoded.len() {
+                assert!(decoded.is_none());
+            } else {
+                assert_eq!(&decoded.unwrap()[..], &[1u8; 300]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_too_large() {
+        let mut buf = frame(&[0u8; 65]);
+        let mut codec = VarintLengthCodec::with_max_frame_len(64);
+
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_decode_unterminated_prefix() {
+        let mut codec = VarintLengthCodec::new();
+
+        let mut buf = BytesMut::from(&[0x80u8; 9][..]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+
+        buf.put_u8(0x80);
+        assert!(codec.decode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_encode_frame_too_large() {
+        let mut codec = VarintLengthCodec::with_max_frame_len(4);
+        let mut buf = BytesMut::new();
+
+        assert!(
+            codec
+                .encode(Bytes::from_static(b"hello"), &mut buf)
+                .is_err()
+        );
+        assert!(buf.is_empty());
+    }
+}
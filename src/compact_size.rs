@@ -0,0 +1,373 @@
+//! Bitcoin `CompactSize` unsigned integers.
+//!
+//! # Encoding Format
+//!
+//! Unlike LEB128, `CompactSize` uses the first byte as a tag that selects the
+//! width of a little-endian integer that follows:
+//!
+//! ```text
+//! value <= 0xFC          → [value]                      (1 byte)
+//! value <= 0xFFFF        → [0xFD, u16 little-endian]    (3 bytes)
+//! value <= 0xFFFF_FFFF   → [0xFE, u32 little-endian]    (5 bytes)
+//! otherwise              → [0xFF, u64 little-endian]    (9 bytes)
+//! ```
+//!
+//! The format itself allows a value to be written with a wider tag than
+//! necessary (e.g. `[0xFD, 0x01, 0x00]` for 1). Bitcoin Core rejects such
+//! non-minimal encodings; use [`decode_u64_canonical`] or
+//! [`io::read_u64_canonical`] to do the same.
+
+use crate::error::UVarintError;
+
+const TAG_U16: u8 = 0xFD;
+const TAG_U32: u8 = 0xFE;
+const TAG_U64: u8 = 0xFF;
+
+/// Returns the number of bytes needed to encode `value` as a `CompactSize`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::compact_size::encoded_len;
+///
+/// assert_eq!(encoded_len(0xFC), 1);
+/// assert_eq!(encoded_len(0xFD), 3);
+/// assert_eq!(encoded_len(0x1_0000), 5);
+/// assert_eq!(encoded_len(0x1_0000_0000), 9);
+/// ```
+pub fn encoded_len(value: u64) -> usize {
+    match value {
+        0..=0xFC => 1,
+        0xFD..=0xFFFF => 3,
+        0x1_0000..=0xFFFF_FFFF => 5,
+        _ => 9,
+    }
+}
+
+/// Encodes a u64 value as a `CompactSize`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::compact_size::encode_u64;
+///
+/// assert_eq!(encode_u64(252), vec![0xFC]);
+/// assert_eq!(encode_u64(253), vec![0xFD, 0xFD, 0x00]);
+/// assert_eq!(encode_u64(0x1_0000), vec![0xFE, 0x00, 0x00, 0x01, 0x00]);
+/// ```
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    let mut result = vec![0u8; encoded_len(value)];
+    encode_u64_into(value, &mut result).expect("buffer sized by encoded_len");
+    result
+}
+
+/// Encodes a u64 as a `CompactSize` into a provided buffer, returning the
+/// number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::compact_size::encode_u64_into;
+///
+/// let mut buf = [0u8; 9];
+/// let n = encode_u64_into(515, &mut buf).unwrap();
+/// assert_eq!(n, 3);
+/// assert_eq!(&buf[..n], &[0xFD, 0x03, 0x02]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u64_into(value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let len = encoded_len(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    match len {
+        1 => buf[0] = value as u8,
+        3 => {
+            buf[0] = TAG_U16;
+            buf[1..3].copy_from_slice(&(value as u16).to_le_bytes());
+        }
+        5 => {
+            buf[0] = TAG_U32;
+            buf[1..5].copy_from_slice(&(value as u32).to_le_bytes());
+        }
+        _ => {
+            buf[0] = TAG_U64;
+            buf[1..9].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    Ok(len)
+}
+
+/// Decodes a `CompactSize` from a byte slice.
+///
+/// Non-minimal encodings are accepted; see [`decode_u64_canonical`] for the
+/// strict variant.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::compact_size::decode_u64;
+///
+/// assert_eq!(decode_u64(&[0x05]).unwrap(), (1, 5));
+/// assert_eq!(decode_u64(&[0xFD, 0x03, 0x02]).unwrap(), (3, 515));
+///
+/// // Non-minimal, but still accepted
+/// assert_eq!(decode_u64(&[0xFD, 0x05, 0x00]).unwrap(), (3, 5));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the value does.
+pub fn decode_u64(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    let &tag = data.first().ok_or(UVarintError::Incomplete)?;
+
+    let width = match tag {
+        TAG_U16 => 2,
+        TAG_U32 => 4,
+        TAG_U64 => 8,
+        _ => return Ok((1, tag as u64)),
+    };
+
+    let payload = data.get(1..1 + width).ok_or(UVarintError::Incomplete)?;
+    let mut le = [0u8; 8];
+    le[..width].copy_from_slice(payload);
+
+    Ok((1 + width, u64::from_le_bytes(le)))
+}
+
+/// Decodes a `CompactSize` from a byte slice, rejecting non-minimal encodings
+/// the same way Bitcoin Core does.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::{UVarintError, compact_size::decode_u64_canonical};
+///
+/// assert_eq!(decode_u64_canonical(&[0xFD, 0xFD, 0x00]).unwrap(), (3, 253));
+/// assert!(matches!(
+///     decode_u64_canonical(&[0xFD, 0x05, 0x00]),
+///     Err(UVarintError::NonCanonical)
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the value does,
+/// or `UVarintError::NonCanonical` if a shorter encoding exists.
+pub fn decode_u64_canonical(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    let (n, value) = decode_u64(data)?;
+
+    if encoded_len(value) != n {
+        return Err(UVarintError::NonCanonical);
+    }
+
+    Ok((n, value))
+}
+
+/// Streaming `CompactSize` helpers over `std::io`.
+pub mod io {
+    use std::io::{Read, Write};
+
+    use super::{TAG_U16, TAG_U32, TAG_U64, decode_u64, decode_u64_canonical, encode_u64_into};
+    use crate::error::UVarintError;
+
+    /// Reads the tag byte and its payload, returning the raw bytes and their length.
+    fn read_raw<R: Read>(reader: &mut R) -> Result<([u8; 9], usize), UVarintError> {
+        let mut buf = [0u8; 9];
+
+        reader
+            .read_exact(&mut buf[..1])
+            .map_err(|_| UVarintError::Incomplete)?;
+
+        let width = match buf[0] {
+            TAG_U16 => 2,
+            TAG_U32 => 4,
+            TAG_U64 => 8,
+            _ => 0,
+        };
+
+        reader
+            .read_exact(&mut buf[1..1 + width])
+            .map_err(|_| UVarintError::Incomplete)?;
+
+        Ok((buf, 1 + width))
+    }
+
+    /// Read a `CompactSize` from any `Read` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use uvarint::compact_size::io::read_u64;
+    ///
+    /// let mut cursor = Cursor::new(vec![0xFD, 0x03, 0x02]);
+    /// assert_eq!(read_u64(&mut cursor).unwrap(), 515);
+    /// ```
+    pub fn read_u64<R: Read>(reader: &mut R) -> Result<u64, UVarintError> {
+        let (buf, len) = read_raw(reader)?;
+        let (_, value) = decode_u64(&buf[..len])?;
+        Ok(value)
+    }
+
+    /// Read a `CompactSize` from any `Read` implementation, rejecting non-minimal
+    /// encodings
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use uvarint::compact_size::io::read_u64_canonical;
+    ///
+    /// let mut cursor = Cursor::new(vec![0xFE, 0x05, 0x00, 0x00, 0x00]);
+    /// assert!(read_u64_canonical(&mut cursor).is_err());
+    /// ```
+    pub fn read_u64_canonical<R: Read>(reader: &mut R) -> Result<u64, UVarintError> {
+        let (buf, len) = read_raw(reader)?;
+        let (_, value) = decode_u64_canonical(&buf[..len])?;
+        Ok(value)
+    }
+
+    /// Write a `CompactSize` to any `Write` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uvarint::compact_size::io::write_u64;
+    ///
+    /// let mut buf = Vec::new();
+    /// write_u64(&mut buf, 515).unwrap();
+    /// assert_eq!(buf, vec![0xFD, 0x03, 0x02]);
+    /// ```
+    pub fn write_u64<W: Write>(writer: &mut W, value: u64) -> Result<usize, UVarintError> {
+        let mut buf = [0u8; 9];
+        let n = encode_u64_into(value, &mut buf)?;
+        writer
+            .write_all(&buf[..n])
+            .map_err(UVarintError::WriteFailed)?;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::io::{read_u64, read_u64_canonical, write_u64};
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_encode_boundaries() {
+        assert_eq!(encode_u64(0), vec![0x00]);
+        assert_eq!(encode_u64(0xFC), vec![0xFC]);
+        assert_eq!(encode_u64(0xFD), vec![0xFD, 0xFD, 0x00]);
+        assert_eq!(encode_u64(0xFFFF), vec![0xFD, 0xFF, 0xFF]);
+        assert_eq!(encode_u64(0x1_0000), vec![0xFE, 0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(encode_u64(0xFFFF_FFFF), vec![0xFE, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            encode_u64(0x1_0000_0000),
+            vec![0xFF, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(
+            encode_u64(u64::MAX),
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn test_encode_into_buffer_too_small() {
+        let mut buf = [0u8; 2];
+        assert!(matches!(
+            encode_u64_into(0xFD, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        let values = [
+            0,
+            1,
+            0xFC,
+            0xFD,
+            0xFFFF,
+            0x1_0000,
+            0xFFFF_FFFF,
+            0x1_0000_0000,
+            u64::MAX,
+        ];
+
+        for &value in &values {
+            let encoded = encode_u64(value);
+            assert_eq!(decode_u64(&encoded).unwrap(), (encoded.len(), value));
+            assert_eq!(
+                decode_u64_canonical(&encoded).unwrap(),
+                (encoded.len(), value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(matches!(decode_u64(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            decode_u64(&[0xFD, 0x01]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_u64(&[0xFF, 0x01, 0x02, 0x03]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_decode_non_canonical() {
+        // Each of these fits in a narrower tag.
+        let cases: [&[u8]; 3] = [
+            &[0xFD, 0xFC, 0x00],
+            &[0xFE, 0xFF, 0xFF, 0x00, 0x00],
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00],
+        ];
+
+        for data in cases {
+            assert!(decode_u64(data).is_ok());
+            assert!(matches!(
+                decode_u64_canonical(data),
+                Err(UVarintError::NonCanonical)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_read_write_roundtrip() {
+        let values = [0, 0xFC, 0xFD, 0x1_0000, 0x1_0000_0000];
+
+        let mut buf = Vec::new();
+        for &value in &values {
+            write_u64(&mut buf, value).unwrap();
+        }
+
+        let mut cursor = Cursor::new(buf);
+        for &value in &values {
+            assert_eq!(read_u64_canonical(&mut cursor).unwrap(), value);
+        }
+        assert!(matches!(
+            read_u64(&mut cursor),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_read_non_canonical() {
+        let mut cursor = Cursor::new(vec![0xFD, 0x05, 0x00]);
+        assert!(matches!(
+            read_u64_canonical(&mut cursor),
+            Err(UVarintError::NonCanonical)
+        ));
+    }
+}
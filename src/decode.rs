@@ -52,14 +52,13 @@ pub fn decode_u32(data: &[u8]) -> Result<(usize, u32), UVarintError> {
 
     for (i, &byte) in data.iter().take(5).enumerate() {
         let data_bits = (byte & 0x7F) as u32;
+        let shift = i as u32 * 7;
 
-        value = value
-            .checked_add(
-                data_bits
-                    .checked_shl(i as u32 * 7)
-                    .ok_or(UVarintError::Overflow)?,
-            )
-            .ok_or(UVarintError::Overflow)?;
+        // The last byte may only carry the bits that are left
+        if data_bits > u32::MAX >> shift {
+            return Err(UVarintError::Overflow);
+        }
+        value |= data_bits << shift;
 
         if (byte & 0x80) == 0 {
             return Ok((i + 1, value));
@@ -121,14 +120,13 @@ pub fn decode_u64(data: &[u8]) -> Result<(usize, u64), UVarintError> {
 
     for (i, &byte) in data.iter().take(10).enumerate() {
         let data_bits = (byte & 0x7F) as u64;
+        let shift = i as u32 * 7;
 
-        value = value
-            .checked_add(
-                data_bits
-                    .checked_shl(i as u32 * 7)
-                    .ok_or(UVarintError::Overflow)?,
-            )
-            .ok_or(UVarintError::Overflow)?;
+        // The last byte may only carry the bits that are left
+        if data_bits > u64::MAX >> shift {
+            return Err(UVarintError::Overflow);
+        }
+        value |= data_bits << shift;
 
         if (byte & 0x80) == 0 {
             return Ok((i + 1, value));
@@ -165,14 +163,13 @@ pub fn decode_u128(data: &[u8]) -> Result<(usize, u128), UVarintError> {
 
     for (i, &byte) in data.iter().take(19).enumerate() {
         let data_bits = (byte & 0x7F) as u128;
+        let shift = i as u32 * 7;
 
-        value = value
-            .checked_add(
-                data_bits
-                    .checked_shl(i as u32 * 7)
-                    .ok_or(UVarintError::Overflow)?,
-            )
-            .ok_or(UVarintError::Overflow)?;
+        // The last byte may only carry the bits that are left
+        if data_bits > u128::MAX >> shift {
+            return Err(UVarintError::Overflow);
+        }
+        value |= data_bits << shift;
 
         if (byte & 0x80) == 0 {
             return Ok((i + 1, value));
@@ -241,4 +238,35 @@ mod tests {
         // Encode: [1111_1111] [0111_1111]
         assert_eq!(decode_u128(&[0xFF, 0x7F]).unwrap(), (2, 16_383));
     }
+
+    #[test]
+    fn test_last_byte_overflow() {
+        // Only the lowest bit of a u64's tenth byte is payload
+        let mut data = vec![0xFF; 9];
+        data.push(0x01);
+        assert_eq!(decode_u64(&data).unwrap(), (10, u64::MAX));
+        data[9] = 0x02;
+        assert!(matches!(decode_u64(&data), Err(UVarintError::Overflow)));
+
+        assert_eq!(
+            decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]).unwrap(),
+            (5, u32::MAX)
+        );
+        assert!(matches!(
+            decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]),
+            Err(UVarintError::Overflow)
+        ));
+
+        let mut data = vec![0xFF; 18];
+        data.push(0x03);
+        assert_eq!(decode_u128(&data).unwrap(), (19, u128::MAX));
+        data[18] = 0x04;
+        assert!(matches!(decode_u128(&data), Err(UVarintError::Overflow)));
+
+        // Ten continuation bytes never end, whatever follows
+        assert!(matches!(
+            decode_u64(&[0x80; 12]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
 }
@@ -0,0 +1,263 @@
+use crate::{
+    decode::{decode_u32, decode_u64},
+    encode::{encode_u32_into, encode_u64_into},
+    error::UVarintError,
+    iter::VarintIter,
+};
+
+/// Encodes a sorted sequence of u32 values as LEB128 varints of the
+/// differences between consecutive values.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_delta_u32;
+///
+/// // Deltas: 100, 200, 0, 1
+/// assert_eq!(
+///     encode_delta_u32(&[100, 300, 300, 301]).unwrap(),
+///     vec![0x64, 0xC8, 0x01, 0x00, 0x01]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::NotSorted` if a value is smaller than the one before
+/// it.
+pub fn encode_delta_u32(values: &[u32]) -> Result<Vec<u8>, UVarintError> {
+    let mut result = Vec::with_capacity(values.len());
+    let mut buf = [0u8; 5];
+    let mut prev = 0;
+
+    for &value in values {
+        let delta = value.checked_sub(prev).ok_or(UVarintError::NotSorted)?;
+        let n = encode_u32_into(delta, &mut buf)?;
+        result.extend_from_slice(&buf[..n]);
+        prev = value;
+    }
+
+    Ok(result)
+}
+
+/// Encodes a sorted sequence of u64 values as LEB128 varints of the
+/// differences between consecutive values.
+///
+/// The first value is encoded as its difference from 0, so the output is
+/// self-contained.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_delta_u64;
+///
+/// // Deltas: 1000, 1, 1
+/// assert_eq!(
+///     encode_delta_u64(&[1000, 1001, 1002]).unwrap(),
+///     vec![0xE8, 0x07, 0x01, 0x01]
+/// );
+/// assert!(encode_delta_u64(&[2, 1]).is_err());
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::NotSorted` if a value is smaller than the one before
+/// it.
+pub fn encode_delta_u64(values: &[u64]) -> Result<Vec<u8>, UVarintError> {
+    let mut result = Vec::with_capacity(values.len());
+    let mut buf = [0u8; 10];
+    let mut prev = 0;
+
+    for &value in values {
+        let delta = value.checked_sub(prev).ok_or(UVarintError::NotSorted)?;
+        let n = encode_u64_into(delta, &mut buf)?;
+        result.extend_from_slice(&buf[..n]);
+        prev = value;
+    }
+
+    Ok(result)
+}
+
+/// Decodes a delta-encoded byte slice back into absolute u32 values.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_delta_u32;
+///
+/// assert_eq!(
+///     decode_delta_u32(&[0x64, 0xC8, 0x01, 0x00, 0x01]).unwrap(),
+///     vec![100, 300, 300, 301]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends mid-varint, or
+/// `UVarintError::Overflow` if a delta or running total does not fit in a u32.
+pub fn decode_delta_u32(data: &[u8]) -> Result<Vec<u32>, UVarintError> {
+    iter_delta_u32(data).collect()
+}
+
+/// Decodes a delta-encoded byte slice back into absolute u64 values.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_delta_u64;
+///
+/// assert_eq!(
+///     decode_delta_u64(&[0xE8, 0x07, 0x01, 0x01]).unwrap(),
+///     vec![1000, 1001, 1002]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends mid-varint, or
+/// `UVarintError::Overflow` if a delta or running total does not fit in a u64.
+pub fn decode_delta_u64(data: &[u8]) -> Result<Vec<u64>, UVarintError> {
+    iter_delta_u64(data).collect()
+}
+
+/// Iterator over delta-encoded varints that yields absolute values.
+///
+/// After the first error the iterator is exhausted.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::{encode_delta_u64, iter_delta_u64};
+///
+/// let data = encode_delta_u64(&[3, 7, 7, 20]).unwrap();
+/// let mut iter = iter_delta_u64(&data);
+///
+/// assert_eq!(iter.next().unwrap().unwrap(), 3);
+/// assert_eq!(iter.next().unwrap().unwrap(), 7);
+/// assert_eq!(iter.next().unwrap().unwrap(), 7);
+/// assert_eq!(iter.next().unwrap().unwrap(), 20);
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeltaIter<'a, T> {
+    inner: VarintIter<'a, T>,
+    prev: T,
+    add: fn(T, T) -> Option<T>,
+}
+
+impl<'a, T> DeltaIter<'a, T> {
+    /// Returns the bytes that have not been decoded yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.inner.remaining()
+    }
+}
+
+impl<T: Copy> Iterator for DeltaIter<'_, T> {
+    type Item = Result<T, UVarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let delta = match self.inner.next()? {
+            Ok(delta) => delta,
+            Err(e) => return Some(Err(e)),
+        };
+
+        match (self.add)(self.prev, delta) {
+            Some(value) => {
+                self.prev = value;
+                Some(Ok(value))
+            }
+            None => {
+                // Stop here; every later value would be offset by the overflow
+                self.inner.finish();
+                Some(Err(UVarintError::Overflow))
+            }
+        }
+    }
+}
+
+impl<T: Copy> std::iter::FusedIterator for DeltaIter<'_, T> {}
+
+/// Returns an iterator yielding the absolute u32 values of a delta-encoded
+/// byte slice.
+pub fn iter_delta_u32(data: &[u8]) -> DeltaIter<'_, u32> {
+    DeltaIter {
+        inner: VarintIter::new(data, decode_u32),
+        prev: 0,
+        add: u32::checked_add,
+    }
+}
+
+/// Returns an iterator yielding the absolute u64 values of a delta-encoded
+/// byte slice.
+pub fn iter_delta_u64(data: &[u8]) -> DeltaIter<'_, u64> {
+    DeltaIter {
+        inner: VarintIter::new(data, decode_u64),
+        prev: 0,
+        add: u64::checked_add,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_roundtrip_u64() {
+        let values = [0, 0, 1, 127, 255, 16_384, 1 << 40, u64::MAX];
+        let encoded = encode_delta_u64(&values).unwrap();
+        assert_eq!(decode_delta_u64(&encoded).unwrap(), values);
+    }
+
+    #[test]
+    fn test_delta_roundtrip_u32() {
+        let values: Vec<u32> = (0..1_000).map(|i| i * i).collect();
+        let encoded = encode_delta_u32(&values).unwrap();
+        assert_eq!(decode_delta_u32(&encoded).unwrap(), values);
+    }
+
+    #[test]
+    fn test_delta_is_smaller_than_absolute() {
+        let values: Vec<u64> = (1_000_000..1_000_100).collect();
+        let delta = encode_delta_u64(&values).unwrap();
+        let absolute: usize = values.iter().map(|&v| crate::encoded_len_u64(v)).sum();
+
+        assert_eq!(delta.len(), 3 + 99);
+        assert!(delta.len() < absolute);
+    }
+
+    #[test]
+    fn test_delta_empty() {
+        assert!(encode_delta_u64(&[]).unwrap().is_empty());
+        assert!(decode_delta_u64(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delta_unsorted() {
+        assert!(matches!(
+            encode_delta_u64(&[1, 5, 4]),
+            Err(UVarintError::NotSorted)
+        ));
+        assert!(matches!(
+            encode_delta_u32(&[10, 9]),
+            Err(UVarintError::NotSorted)
+        ));
+    }
+
+    #[test]
+    fn test_delta_overflow() {
+        // u32::MAX followed by a delta of 1
+        let data = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x01, 0x01];
+        let mut iter = iter_delta_u32(&data);
+
+        assert_eq!(iter.next().unwrap().unwrap(), u32::MAX);
+        assert!(matches!(iter.next(), Some(Err(UVarintError::Overflow))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_delta_incomplete() {
+        assert!(matches!(
+            decode_delta_u64(&[0x01, 0x80]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+}
@@ -1,5 +1,55 @@
 use crate::error::UVarintError;
 
+/// Returns the number of bytes `encode_u32` would produce for `value`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encoded_len_u32;
+///
+/// assert_eq!(encoded_len_u32(0), 1);
+/// assert_eq!(encoded_len_u32(127), 1);
+/// assert_eq!(encoded_len_u32(128), 2);
+/// assert_eq!(encoded_len_u32(u32::MAX), 5);
+/// ```
+pub const fn encoded_len_u32(value: u32) -> usize {
+    encoded_len_u128(value as u128)
+}
+
+/// Returns the number of bytes `encode_u64` would produce for `value`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encoded_len_u64;
+///
+/// assert_eq!(encoded_len_u64(300), 2);
+/// assert_eq!(encoded_len_u64(u64::MAX), 10);
+/// ```
+pub const fn encoded_len_u64(value: u64) -> usize {
+    encoded_len_u128(value as u128)
+}
+
+/// Returns the number of bytes `encode_u128` would produce for `value`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encoded_len_u128;
+///
+/// assert_eq!(encoded_len_u128(300), 2);
+/// assert_eq!(encoded_len_u128(u128::MAX), 19);
+/// ```
+pub const fn encoded_len_u128(value: u128) -> usize {
+    // Every byte carries 7 bits; 0 still takes one byte.
+    let bits = u128::BITS - value.leading_zeros();
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7) as usize
+    }
+}
+
 /// Encodes a u32 value into unsigned varint format.
 ///
 /// # Varint Encoding Algorithm
@@ -76,9 +126,16 @@ pub fn encode_u32(mut value: u32) -> Vec<u8> {
 /// assert_eq!(n, 2);
 /// assert_eq!(&buf[..n], &[0xAC, 0x02]);
 /// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` with the required length if the
+/// encoded value does not fit. Nothing is written in that case.
 pub fn encode_u32_into(mut value: u32, buf: &mut [u8]) -> Result<usize, UVarintError> {
-    if buf.is_empty() {
-        return Err(UVarintError::BufferTooSmall);
+    // Checked up front so a failed encode leaves `buf` untouched
+    let len = encoded_len_u32(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
     }
 
     // Special case: 0
@@ -90,10 +147,6 @@ pub fn encode_u32_into(mut value: u32, buf: &mut [u8]) -> Result<usize, UVarintE
     let mut i = 0;
 
     while value > 0 {
-        if i >= buf.len() {
-            return Err(UVarintError::BufferTooSmall);
-        }
-
         let mut byte = (value & 0x7F) as u8;
         value >>= 7;
 
@@ -192,10 +245,13 @@ pub fn encode_u64(mut value: u64) -> Vec<u8> {
 ///
 /// # Errors
 ///
-/// Returns `UVarintError::BufferTooSmall` if the buffer is too small.
+/// Returns `UVarintError::BufferTooSmall` with the required length if the
+/// encoded value does not fit. Nothing is written in that case.
 pub fn encode_u64_into(mut value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
-    if buf.is_empty() {
-        return Err(UVarintError::BufferTooSmall);
+    // Checked up front so a failed encode leaves `buf` untouched
+    let len = encoded_len_u64(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
     }
 
     // Special case: 0
@@ -207,10 +263,6 @@ pub fn encode_u64_into(mut value: u64, buf: &mut [u8]) -> Result<usize, UVarintE
     let mut i = 0;
 
     while value > 0 {
-        if i >= buf.len() {
-            return Err(UVarintError::BufferTooSmall);
-        }
-
         let mut byte = (value & 0x7F) as u8;
         value >>= 7;
 
@@ -280,10 +332,13 @@ pub fn encode_u128(mut value: u128) -> Vec<u8> {
 ///
 /// # Errors
 ///
-/// Returns `UVarintError::BufferTooSmall` if the buffer is too small.
+/// Returns `UVarintError::BufferTooSmall` with the required length if the
+/// encoded value does not fit. Nothing is written in that case.
 pub fn encode_u128_into(mut value: u128, buf: &mut [u8]) -> Result<usize, UVarintError> {
-    if buf.is_empty() {
-        return Err(UVarintError::BufferTooSmall);
+    // Checked up front so a failed encode leaves `buf` untouched
+    let len = encoded_len_u128(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
     }
 
     // Special case: 0
@@ -295,10 +350,6 @@ pub fn encode_u128_into(mut value: u128, buf: &mut [u8]) -> Result<usize, UVarin
     let mut i = 0;
 
     while value > 0 {
-        if i >= buf.len() {
-            return Err(UVarintError::BufferTooSmall);
-        }
-
         let mut byte = (value & 0x7F) as u8;
         value >>= 7;
 
@@ -317,6 +368,31 @@ pub fn encode_u128_into(mut value: u128, buf: &mut [u8]) -> Result<usize, UVarin
 mod encode_tests {
     use super::*;
 
+    #[test]
+    fn test_encoded_len_matches_encode() {
+        let values = [
+            0,
+            1,
+            127,
+            128,
+            16_383,
+            16_384,
+            u32::MAX as u128,
+            u64::MAX as u128,
+            u128::MAX,
+        ];
+
+        for &value in &values {
+            assert_eq!(encoded_len_u128(value), encode_u128(value).len());
+            if let Ok(v) = u64::try_from(value) {
+                assert_eq!(encoded_len_u64(v), encode_u64(v).len());
+            }
+            if let Ok(v) = u32::try_from(value) {
+                assert_eq!(encoded_len_u32(v), encode_u32(v).len());
+            }
+        }
+    }
+
     #[test]
     fn test_encode_u32_single_byte() {
         assert_eq!(encode_u32(0), vec![0x00]);
@@ -488,4 +564,31 @@ mod encode_tests {
             ]
         );
     }
+
+    #[test]
+    fn test_encode_into_buffer_too_small_writes_nothing() {
+        let mut buf = [0xAAu8; 4];
+
+        assert!(matches!(
+            encode_u32_into(u32::MAX, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 5 })
+        ));
+        assert!(matches!(
+            encode_u64_into(u64::MAX, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 10 })
+        ));
+        assert!(matches!(
+            encode_u128_into(u128::MAX, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 19 })
+        ));
+        assert!(matches!(
+            encode_u64_into(0, &mut []),
+            Err(UVarintError::BufferTooSmall { needed: 1 })
+        ));
+        assert_eq!(buf, [0xAA; 4]);
+
+        // Growing to the reported size succeeds
+        let mut buf = vec![0u8; 10];
+        assert_eq!(encode_u64_into(u64::MAX, &mut buf).unwrap(), 10);
+    }
 }
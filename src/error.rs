@@ -8,12 +8,57 @@ pub enum UVarintError {
     #[error("Varint overflow - value too large")]
     Overflow,
 
-    #[error("Buffer too small")]
-    BufferTooSmall,
+    #[error("Buffer too small: {needed} bytes needed")]
+    BufferTooSmall { needed: usize },
 
     #[error("Invalid UTF-8 string")]
     InvalidUtf8,
 
     #[error("Write operation failed")]
-    WriteFailed,
+    WriteFailed(#[source] std::io::Error),
+
+    #[error("Non-canonical encoding")]
+    NonCanonical,
+
+    #[error("Input is not sorted")]
+    NotSorted,
+
+    #[error("Invalid git object type {0}")]
+    InvalidObjectType(u8),
+
+    #[error("Invalid block encoding {0}")]
+    InvalidBlockEncoding(u8),
+
+    #[error("Frame length {len} exceeds maximum {max}")]
+    FrameTooLarge { len: u64, max: usize },
+
+    #[error("Invalid variant index {0}")]
+    InvalidVariant(u64),
+
+    #[error("Invalid tag byte {0}")]
+    InvalidTag(u8),
+
+    #[error("Invalid field `{field}`: {source}")]
+    Field {
+        field: &'static str,
+        source: Box<UVarintError>,
+    },
+
+    #[error("Block of {count} values exceeds maximum {max}")]
+    BlockTooLarge { count: u64, max: usize },
+
+    #[error("Sequence of {count} elements exceeds maximum {max}")]
+    SequenceTooLong { count: u64, max: usize },
+
+    #[error("At offset {offset}: {source}")]
+    AtOffset {
+        offset: u64,
+        source: Box<UVarintError>,
+    },
+}
+
+impl From<UVarintError> for std::io::Error {
+    fn from(err: UVarintError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
 }
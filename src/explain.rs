@@ -0,0 +1,215 @@
+use std::fmt;
+
+use crate::{decode::decode_u64, error::UVarintError};
+
+/// One byte of a varint as seen by the decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteStep {
+    /// Position of the byte in the input.
+    pub offset: usize,
+    /// The raw byte.
+    pub byte: u8,
+    /// The lower 7 bits, `byte & 0x7F`.
+    pub data_bits: u8,
+    /// Whether the MSB is set, i.e. more bytes follow.
+    pub continuation: bool,
+    /// How far `data_bits` is shifted before it is added, `7 * offset`.
+    pub shift: u32,
+    /// The decoded value after this byte has been added.
+    pub value: u64,
+}
+
+/// A byte-by-byte breakdown of decoding a single `u64` varint, returned by
+/// [`explain_u64`].
+///
+/// The `Display` impl renders a table with one row per byte followed by the
+/// outcome, which makes it handy in assertion messages:
+///
+/// ```text
+/// byte  hex   binary       data_bits  cont  shift  value
+///    0  0xAC  0b1010_1100         44     1      0  44
+///    1  0x02  0b0000_0010          2     0      7  300
+/// => 300 (2 bytes)
+/// ```
+#[derive(Debug)]
+pub struct Explanation {
+    steps: Vec<ByteStep>,
+    result: Result<(usize, u64), UVarintError>,
+}
+
+impl Explanation {
+    /// The bytes the decoder looked at, in order.
+    pub fn steps(&self) -> &[ByteStep] {
+        &self.steps
+    }
+
+    /// The bytes consumed and the decoded value, or the error decoding hit.
+    pub fn result(&self) -> Result<(usize, u64), &UVarintError> {
+        self.result.as_ref().copied()
+    }
+
+    /// The decoded value, if decoding succeeded.
+    pub fn value(&self) -> Option<u64> {
+        self.result.as_ref().ok().map(|&(_, value)| value)
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "byte  hex   binary       data_bits  cont  shift  value")?;
+
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{:>4}  0x{:02X}  0b{:04b}_{:04b}  {:>9}  {:>4}  {:>5}  {}",
+                step.offset,
+                step.byte,
+                step.byte >> 4,
+                step.byte & 0x0F,
+                step.data_bits,
+                u8::from(step.continuation),
+                step.shift,
+                step.value,
+            )?;
+        }
+
+        match &self.result {
+            Ok((1, value)) => write!(f, "=> {value} (1 byte)"),
+            Ok((n, value)) => write!(f, "=> {value} ({n} bytes)"),
+            Err(e) => write!(f, "=> error: {e}"),
+        }
+    }
+}
+
+/// Decodes a `u64` varint from the start of `data`, recording each step.
+///
+/// The outcome is exactly what [`decode_u64`] returns for the same input,
+/// malformed or not; the breakdown additionally keeps every byte the
+/// decoder looked at, including the one it failed on.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::explain_u64;
+///
+/// let explanation = explain_u64(&[0xAC, 0x02]);
+/// assert_eq!(explanation.value(), Some(300));
+///
+/// let steps = explanation.steps();
+/// assert_eq!((steps[0].data_bits, steps[0].continuation), (44, true));
+/// assert_eq!((steps[1].shift, steps[1].value), (7, 300));
+///
+/// println!("{explanation}");
+/// ```
+pub fn explain_u64(data: &[u8]) -> Explanation {
+    let mut steps = Vec::new();
+    let mut value: u64 = 0;
+
+    // Walk the same bytes `decode_u64` reads: up to the terminating one, or
+    // ten at most
+    for (i, &byte) in data.iter().take(10).enumerate() {
+        let data_bits = byte & 0x7F;
+        let continuation = (byte & 0x80) != 0;
+        let shift = i as u32 * 7;
+        value |= u64::from(data_bits) << shift;
+
+        steps.push(ByteStep {
+            offset: i,
+            byte,
+            data_bits,
+            continuation,
+            shift,
+            value,
+        });
+
+        if !continuation {
+            break;
+        }
+    }
+
+    Explanation {
+        steps,
+        result: decode_u64(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_u64;
+
+    #[test]
+    fn test_explain_matches_decode() {
+        for value in [0, 1, 127, 128, 300, 16_384, u32::MAX as u64, u64::MAX] {
+            let encoded = encode_u64(value);
+            let explanation = explain_u64(&encoded);
+
+            assert_eq!(explanation.result().unwrap(), decode_u64(&encoded).unwrap());
+            assert_eq!(explanation.steps().len(), encoded.len());
+            assert!(explanation.steps().last().is_some_and(|s| !s.continuation));
+        }
+
+        let mut tenth_overflows = vec![0xFF; 9];
+        tenth_overflows.push(0x02);
+        let malformed: [&[u8]; 7] = [
+            &[],
+            &[0xAC],
+            &[0x80; 10],
+            &[0x80; 12],
+            &tenth_overflows,
+            // Overlong encodings of 1 and 0
+            &[0x81, 0x80, 0x00],
+            &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00],
+        ];
+        for data in malformed {
+            let explanation = explain_u64(data);
+            let decoded = decode_u64(data);
+            assert_eq!(
+                explanation.result().map_err(ToString::to_string),
+                decoded.map_err(|e| e.to_string()),
+                "{data:02X?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_table() {
+        let expected = "\
+byte  hex   binary       data_bits  cont  shift  value
+   0  0xAC  0b1010_1100         44     1      0  44
+   1  0x02  0b0000_0010          2     0      7  300
+=> 300 (2 bytes)";
+
+        assert_eq!(explain_u64(&[0xAC, 0x02, 0xFF]).to_string(), expected);
+        assert!(explain_u64(&[0x05]).to_string().ends_with("=> 5 (1 byte)"));
+    }
+
+    #[test]
+    fn test_explain_errors_keep_steps() {
+        let explanation = explain_u64(&[0xAC]);
+        assert_eq!(explanation.steps().len(), 1);
+        assert!(matches!(
+            explanation.result(),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(
+            explanation
+                .to_string()
+                .ends_with("=> error: Incomplete varint data")
+        );
+
+        // Tenth byte carries bits beyond u64
+        let mut data = vec![0xFF; 9];
+        data.push(0x02);
+        let explanation = explain_u64(&data);
+        assert_eq!(explanation.steps().len(), 10);
+        assert!(matches!(explanation.result(), Err(UVarintError::Overflow)));
+
+        let explanation = explain_u64(&[0x80; 12]);
+        assert_eq!(explanation.steps().len(), 10);
+        assert!(matches!(
+            explanation.result(),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    decode::decode_u64,
+    encode::{encode_u64_into, encoded_len_u64},
+    error::UVarintError,
+};
+
+/// Returns the number of bytes a frame holding `payload_len` bytes takes,
+/// including its varint length prefix.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encoded_frame_len;
+///
+/// assert_eq!(encoded_frame_len(5), 6);
+/// assert_eq!(encoded_frame_len(300), 302);
+/// ```
+pub fn encoded_frame_len(payload_len: usize) -> usize {
+    encoded_len_u64(payload_len as u64) + payload_len
+}
+
+/// Prefixes `payload` with its varint-encoded length.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_frame;
+///
+/// assert_eq!(encode_frame(b"hi"), vec![0x02, b'h', b'i']);
+/// ```
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; encoded_frame_len(payload.len())];
+    encode_frame_into(payload, &mut result).expect("buffer sized by encoded_frame_len");
+    result
+}
+
+/// Writes a length-prefixed frame into a provided buffer, returning the number
+/// of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_frame_into;
+///
+/// let mut buf = [0u8; 16];
+/// let n = encode_frame_into(b"hello", &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0x05, b'h', b'e', b'l', b'l', b'o']);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the whole frame does not fit.
+/// Nothing is written in that case.
+pub fn encode_frame_into(payload: &[u8], buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let needed = encoded_frame_len(payload.len());
+    if buf.len() < needed {
+        return Err(UVarintError::BufferTooSmall { needed });
+    }
+
+    let n = encode_u64_into(payload.len() as u64, buf)?;
+    buf[n..n + payload.len()].copy_from_slice(payload);
+
+    Ok(n + payload.len())
+}
+
+/// Reads a length-prefixed frame from a byte slice, returning the bytes
+/// consumed and the payload borrowed from `data`.
+///
+/// The length is checked against `max_len` before the payload is touched, so
+/// a hostile prefix cannot make the caller trust a huge frame.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_frame;
+///
+/// let data = [0x02, b'h', b'i', 0x00];
+/// assert_eq!(decode_frame(&data, 1024).unwrap(), (3, &b"hi"[..]));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::FrameTooLarge` if the length exceeds `max_len`, or
+/// `UVarintError::Incomplete` if the slice ends before the payload does.
+pub fn decode_frame(data: &[u8], max_len: usize) -> Result<(usize, &[u8]), UVarintError> {
+    let (n, len) = decode_u64(data)?;
+    let len = checked_frame_len(len, max_len)?;
+
+    let end = n.checked_add(len).ok_or(UVarintError::Incomplete)?;
+    let payload = data.get(n..end).ok_or(UVarintError::Incomplete)?;
+    Ok((end, payload))
+}
+
+/// Converts a decoded frame length to `usize`, enforcing `max_len`.
+pub(crate) fn checked_frame_len(len: u64, max_len: usize) -> Result<usize, UVarintError> {
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len => Ok(len),
+        _ => Err(UVarintError::FrameTooLarge { len, max: max_len }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        for len in [0, 1, 127, 128, 300, 20_000] {
+            let payload: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let encoded = encode_frame(&payload);
+            assert_eq!(encoded.len(), encoded_frame_len(len));
+
+            let (n, decoded) = decode_frame(&encoded, len).unwrap();
+            assert_eq!(n, encoded.len());
+            assert_eq!(decoded, payload.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_is_zero_copy() {
+        let data = encode_frame(b"payload");
+        let (_, payload) = decode_frame(&data, 64).unwrap();
+        assert_eq!(payload.as_ptr(), data[1..].as_ptr());
+    }
+
+    #[test]
+    fn test_decode_frame_too_large() {
+        let data = encode_frame(&[0u8; 10]);
+        assert!(matches!(
+            decode_frame(&data, 9),
+            Err(UVarintError::FrameTooLarge { len: 10, max: 9 })
+        ));
+
+        // A hostile length is rejected even though no payload follows it
+        let hostile = crate::encode_u64(u64::MAX);
+        assert!(matches!(
+            decode_frame(&hostile, 1 << 20),
+            Err(UVarintError::FrameTooLarge { .. })
+        ));
+
+        // Without a limit, the huge length must not wrap around the prefix
+        assert!(matches!(
+            decode_frame(&hostile, usize::MAX),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_decode_frame_incomplete() {
+        assert!(matches!(
+            decode_frame(&[], 8),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_frame(&[0x03, b'a', b'b'], 8),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_encode_frame_buffer_too_small() {
+        let mut buf = [0xAAu8; 3];
+        assert!(matches!(
+            encode_frame_into(b"abc", &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 4 })
+        ));
+        assert_eq!(buf, [0xAA; 3]);
+    }
+}
@@ -0,0 +1,530 @@
+//! Git packfile varints.
+//!
+//! Packfiles use two distinct variable-length encodings:
+//!
+//! - **Object headers** start every packed object. The first byte holds a
+//!   continuation bit, a 3-bit object type and the low 4 bits of the
+//!   uncompressed size; the remaining size bits follow as little-endian 7-bit
+//!   groups, exactly like LEB128.
+//! - **`OFS_DELTA` offsets** give the distance back to a delta's base object.
+//!   They are big-endian 7-bit groups, and every continuation adds 1 to the
+//!   accumulated value before shifting. The bias means no two byte sequences
+//!   decode to the same offset, so there are no redundant encodings.
+//!
+//! ```text
+//! Object header (blob, size 300):
+//!   300 = 0b1_0010_1100
+//!   byte 1: [1 | 011 | 1100]  continuation, type 3, size bits 0-3
+//!   byte 2: [0 | 001_0010]    size bits 4-10
+//!   → [0xBC, 0x12]
+//!
+//! Offset 300:
+//!   300 >> 7 = 2, minus the bias = 1
+//!   byte 1: [1 | 000_0001]
+//!   byte 2: [0 | 010_1100]    300 & 0x7F
+//!   → [0x81, 0x2C]
+//! ```
+
+use crate::error::UVarintError;
+
+/// Maximum encoded length of a u64 in either encoding.
+const MAX_LEN: usize = 10;
+
+/// The type of an object stored in a packfile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    Commit = 1,
+    Tree = 2,
+    Blob = 3,
+    Tag = 4,
+    OfsDelta = 6,
+    RefDelta = 7,
+}
+
+impl TryFrom<u8> for ObjectType {
+    type Error = UVarintError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ObjectType::Commit),
+            2 => Ok(ObjectType::Tree),
+            3 => Ok(ObjectType::Blob),
+            4 => Ok(ObjectType::Tag),
+            6 => Ok(ObjectType::OfsDelta),
+            7 => Ok(ObjectType::RefDelta),
+            _ => Err(UVarintError::InvalidObjectType(value)),
+        }
+    }
+}
+
+/// Encodes a packed object header.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::{ObjectType, encode_object_header};
+///
+/// assert_eq!(encode_object_header(ObjectType::Blob, 300), vec![0xBC, 0x12]);
+/// assert_eq!(encode_object_header(ObjectType::Commit, 15), vec![0x1F]);
+/// ```
+pub fn encode_object_header(kind: ObjectType, size: u64) -> Vec<u8> {
+    let mut buf = [0u8; MAX_LEN];
+    let n = encode_object_header_into(kind, size, &mut buf).expect("MAX_LEN fits any u64");
+    buf[..n].to_vec()
+}
+
+/// Encodes a packed object header into a provided buffer, returning the number
+/// of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::{ObjectType, encode_object_header_into};
+///
+/// let mut buf = [0u8; 10];
+/// let n = encode_object_header_into(ObjectType::Blob, 300, &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0xBC, 0x12]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_object_header_into(
+    kind: ObjectType,
+    mut size: u64,
+    buf: &mut [u8],
+) -> Result<usize, UVarintError> {
+    // 4 size bits in the first byte, 7 in each one after it
+    let len = 1 + (u64::BITS - (size >> 4).leading_zeros()).div_ceil(7) as usize;
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    let mut byte = ((kind as u8) << 4) | (size & 0x0F) as u8;
+    size >>= 4;
+
+    let mut i = 0;
+
+    loop {
+        if size == 0 {
+            buf[i] = byte;
+            return Ok(i + 1);
+        }
+
+        buf[i] = byte | 0x80;
+        byte = (size & 0x7F) as u8;
+        size >>= 7;
+        i += 1;
+    }
+}
+
+/// Decodes a packed object header, returning the bytes consumed, the object
+/// type and its uncompressed size.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::{ObjectType, decode_object_header};
+///
+/// assert_eq!(
+///     decode_object_header(&[0xBC, 0x12]).unwrap(),
+///     (2, ObjectType::Blob, 300)
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the header does,
+/// `UVarintError::Overflow` if the size does not fit in a u64, or
+/// `UVarintError::InvalidObjectType` for the reserved types 0 and 5.
+pub fn decode_object_header(data: &[u8]) -> Result<(usize, ObjectType, u64), UVarintError> {
+    let &first = data.first().ok_or(UVarintError::Incomplete)?;
+
+    let kind = ObjectType::try_from((first >> 4) & 0x07)?;
+    let mut size = (first & 0x0F) as u64;
+
+    if (first & 0x80) == 0 {
+        return Ok((1, kind, size));
+    }
+
+    for (i, &byte) in data.iter().enumerate().skip(1).take(MAX_LEN - 1) {
+        let data_bits = (byte & 0x7F) as u64;
+        let shift = 4 + (i as u32 - 1) * 7;
+
+        let shifted = data_bits
+            .checked_shl(shift)
+            .filter(|v| v >> shift == data_bits)
+            .ok_or(UVarintError::Overflow)?;
+        size |= shifted;
+
+        if (byte & 0x80) == 0 {
+            return Ok((i + 1, kind, size));
+        }
+    }
+
+    if data.len() >= MAX_LEN {
+        return Err(UVarintError::Overflow);
+    }
+
+    Err(UVarintError::Incomplete)
+}
+
+/// Encodes an `OFS_DELTA` base offset.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::encode_offset;
+///
+/// assert_eq!(encode_offset(127), vec![0x7F]);
+/// assert_eq!(encode_offset(128), vec![0x80, 0x00]);
+/// assert_eq!(encode_offset(300), vec![0x81, 0x2C]);
+/// ```
+pub fn encode_offset(value: u64) -> Vec<u8> {
+    let mut buf = [0u8; MAX_LEN];
+    let n = encode_offset_into(value, &mut buf).expect("MAX_LEN fits any u64");
+    buf[..n].to_vec()
+}
+
+/// Encodes an `OFS_DELTA` base offset into a provided buffer, returning the
+/// number of bytes written.
+///
+/// # Step-by-Step Example: Encoding 300
+///
+/// The groups are produced least significant first, so they are written from
+/// the back of a scratch buffer, subtracting the bias from every group that
+/// has a continuation bit:
+///
+/// ```text
+/// pos 9: 300 & 0x7F = 44                   → 0x2C
+/// value = 300 >> 7 = 2
+/// pos 8: (2 - 1) & 0x7F = 1, MSB set       → 0x81
+/// value = (2 - 1) >> 7 = 0, done
+/// Output: [0x81, 0x2C]
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::encode_offset_into;
+///
+/// let mut buf = [0u8; 10];
+/// let n = encode_offset_into(300, &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0x81, 0x2C]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_offset_into(mut value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let mut scratch = [0u8; MAX_LEN];
+    let mut pos = MAX_LEN - 1;
+
+    scratch[pos] = (value & 0x7F) as u8;
+    value >>= 7;
+
+    while value > 0 {
+        value -= 1;
+        pos -= 1;
+        scratch[pos] = 0x80 | (value & 0x7F) as u8;
+        value >>= 7;
+    }
+
+    let len = MAX_LEN - pos;
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    buf[..len].copy_from_slice(&scratch[pos..]);
+    Ok(len)
+}
+
+/// Decodes an `OFS_DELTA` base offset from a byte slice.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::git::decode_offset;
+///
+/// assert_eq!(decode_offset(&[0x81, 0x2C]).unwrap(), (2, 300));
+/// assert_eq!(decode_offset(&[0x80, 0x00]).unwrap(), (2, 128));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the offset does,
+/// or `UVarintError::Overflow` if the offset does not fit in a u64.
+pub fn decode_offset(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    let mut value: u64 = 0;
+
+    for (i, &byte) in data.iter().take(MAX_LEN).enumerate() {
+        if i > 0 {
+            value = value
+                .checked_add(1)
+                .filter(|v| v.leading_zeros() >= 7)
+                .ok_or(UVarintError::Overflow)?
+                << 7;
+        }
+
+        value |= (byte & 0x7F) as u64;
+
+        if (byte & 0x80) == 0 {
+            return Ok((i + 1, value));
+        }
+    }
+
+    if data.len() >= MAX_LEN {
+        return Err(UVarintError::Overflow);
+    }
+
+    Err(UVarintError::Incomplete)
+}
+
+/// Streaming pack header and offset helpers over `std::io`.
+pub mod io {
+    use std::io::{Read, Write};
+
+    use super::{
+        MAX_LEN, ObjectType, decode_object_header, decode_offset, encode_object_header,
+        encode_offset,
+    };
+    use crate::error::UVarintError;
+
+    /// Reads bytes up to and including the first one without a continuation bit.
+    fn read_raw<R: Read>(reader: &mut R) -> Result<([u8; MAX_LEN], usize), UVarintError> {
+        let mut buf = [0u8; MAX_LEN];
+
+        for i in 0..MAX_LEN {
+            reader
+                .read_exact(&mut buf[i..i + 1])
+                .map_err(|_| UVarintError::Incomplete)?;
+
+            if (buf[i] & 0x80) == 0 {
+                return Ok((buf, i + 1));
+            }
+        }
+
+        Err(UVarintError::Overflow)
+    }
+
+    /// Read a packed object header from any `Read` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use uvarint::git::{ObjectType, io::read_object_header};
+    ///
+    /// let mut cursor = Cursor::new(vec![0xBC, 0x12]);
+    /// assert_eq!(
+    ///     read_object_header(&mut cursor).unwrap(),
+    ///     (ObjectType::Blob, 300)
+    /// );
+    /// ```
+    pub fn read_object_header<R: Read>(reader: &mut R) -> Result<(ObjectType, u64), UVarintError> {
+        let (buf, len) = read_raw(reader)?;
+        let (_, kind, size) = decode_object_header(&buf[..len])?;
+        Ok((kind, size))
+    }
+
+    /// Write a packed object header to any `Write` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uvarint::git::{ObjectType, io::write_object_header};
+    ///
+    /// let mut buf = Vec::new();
+    /// write_object_header(&mut buf, ObjectType::Blob, 300).unwrap();
+    /// assert_eq!(buf, vec![0xBC, 0x12]);
+    /// ```
+    pub fn write_object_header<W: Write>(
+        writer: &mut W,
+        kind: ObjectType,
+        size: u64,
+    ) -> Result<usize, UVarintError> {
+        let bytes = encode_object_header(kind, size);
+        writer
+            .write_all(&bytes)
+            .map_err(UVarintError::WriteFailed)?;
+        Ok(bytes.len())
+    }
+
+    /// Read an `OFS_DELTA` base offset from any `Read` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use uvarint::git::io::read_offset;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x81, 0x2C]);
+    /// assert_eq!(read_offset(&mut cursor).unwrap(), 300);
+    /// ```
+    pub fn read_offset<R: Read>(reader: &mut R) -> Result<u64, UVarintError> {
+        let (buf, len) = read_raw(reader)?;
+        let (_, value) = decode_offset(&buf[..len])?;
+        Ok(value)
+    }
+
+    /// Write an `OFS_DELTA` base offset to any `Write` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uvarint::git::io::write_offset;
+    ///
+    /// let mut buf = Vec::new();
+    /// write_offset(&mut buf, 300).unwrap();
+    /// assert_eq!(buf, vec![0x81, 0x2C]);
+    /// ```
+    pub fn write_offset<W: Write>(writer: &mut W, value: u64) -> Result<usize, UVarintError> {
+        let bytes = encode_offset(value);
+        writer
+            .write_all(&bytes)
+            .map_err(UVarintError::WriteFailed)?;
+        Ok(bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::io::{read_object_header, read_offset, write_object_header, write_offset};
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_object_header_single_byte() {
+        assert_eq!(encode_object_header(ObjectType::Commit, 0), vec![0x10]);
+        assert_eq!(encode_object_header(ObjectType::Tree, 15), vec![0x2F]);
+        assert_eq!(
+            decode_object_header(&[0x2F]).unwrap(),
+            (1, ObjectType::Tree, 15)
+        );
+    }
+
+    #[test]
+    fn test_object_header_roundtrip() {
+        let kinds = [
+            ObjectType::Commit,
+            ObjectType::Tree,
+            ObjectType::Blob,
+            ObjectType::Tag,
+            ObjectType::OfsDelta,
+            ObjectType::RefDelta,
+        ];
+        let sizes = [0, 15, 16, 300, 2_047, 2_048, u32::MAX as u64, u64::MAX];
+
+        for &kind in &kinds {
+            for &size in &sizes {
+                let encoded = encode_object_header(kind, size);
+                assert_eq!(
+                    decode_object_header(&encoded).unwrap(),
+                    (encoded.len(), kind, size)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_object_header_invalid_type() {
+        assert!(matches!(
+            decode_object_header(&[0x00]),
+            Err(UVarintError::InvalidObjectType(0))
+        ));
+        assert!(matches!(
+            decode_object_header(&[0x50]),
+            Err(UVarintError::InvalidObjectType(5))
+        ));
+    }
+
+    #[test]
+    fn test_object_header_errors() {
+        assert!(matches!(
+            decode_object_header(&[]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_object_header(&[0xBC]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_object_header(&[0xBF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]),
+            Err(UVarintError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_offset_boundaries() {
+        // The bias makes every length start right after the previous maximum.
+        assert_eq!(encode_offset(0), vec![0x00]);
+        assert_eq!(encode_offset(127), vec![0x7F]);
+        assert_eq!(encode_offset(128), vec![0x80, 0x00]);
+        assert_eq!(encode_offset(16_511), vec![0xFF, 0x7F]);
+        assert_eq!(encode_offset(16_512), vec![0x80, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn test_offset_roundtrip() {
+        let values = [
+            0,
+            1,
+            127,
+            128,
+            300,
+            16_511,
+            16_512,
+            u32::MAX as u64,
+            u64::MAX,
+        ];
+
+        for &value in &values {
+            let encoded = encode_offset(value);
+            assert_eq!(decode_offset(&encoded).unwrap(), (encoded.len(), value));
+        }
+    }
+
+    #[test]
+    fn test_offset_errors() {
+        assert!(matches!(decode_offset(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            decode_offset(&[0x81]),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_offset(&[0xFF; 10]),
+            Err(UVarintError::Overflow)
+        ));
+
+        let mut buf = [0u8; 1];
+        assert!(matches!(
+            encode_offset_into(300, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 2 })
+        ));
+        assert!(matches!(
+            encode_object_header_into(ObjectType::Blob, 300, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 2 })
+        ));
+        assert_eq!(buf, [0]);
+    }
+
+    #[test]
+    fn test_read_write_roundtrip() {
+        let mut buf = Vec::new();
+        write_object_header(&mut buf, ObjectType::OfsDelta, 1_000).unwrap();
+        write_offset(&mut buf, 4_096).unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(
+            read_object_header(&mut cursor).unwrap(),
+            (ObjectType::OfsDelta, 1_000)
+        );
+        assert_eq!(read_offset(&mut cursor).unwrap(), 4_096);
+        assert!(matches!(
+            read_offset(&mut cursor),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+}
@@ -0,0 +1,332 @@
+//! Group Varint: four u32 values behind a shared tag byte.
+//!
+//! # Encoding Format
+//!
+//! Values are written in groups of four. Each group starts with a tag byte
+//! holding four 2-bit fields, one per value, each storing `byte_length - 1`.
+//! The values follow as 1-4 little-endian bytes each, with no continuation
+//! bits, so the decoder knows every length before touching the data:
+//!
+//! ```text
+//! values: [1, 300, 70_000, 16_777_216]
+//! lengths:  1    2       3           4
+//!
+//! tag = (0b11 << 6) | (0b10 << 4) | (0b01 << 2) | 0b00 = 0b1110_0100 = 0xE4
+//!
+//! [0xE4] [0x01] [0x2C, 0x01] [0x70, 0x11, 0x01] [0x00, 0x00, 0x00, 0x01]
+//! ```
+//!
+//! A trailing group with fewer than four values still gets a tag byte, but its
+//! unused fields are zero and have no data bytes. The value count is therefore
+//! not recoverable from the bytes alone and must be stored by the caller.
+
+use std::iter::FusedIterator;
+
+use crate::error::UVarintError;
+
+/// Number of values sharing one tag byte.
+const GROUP_SIZE: usize = 4;
+
+/// Number of bytes needed to store `value` without leading zero bytes.
+pub(crate) fn byte_len(value: u32) -> usize {
+    ((u32::BITS - value.leading_zeros()).max(1) as usize).div_ceil(8)
+}
+
+/// Returns the number of bytes `encode_u32_group` would produce for `values`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::group_varint::encoded_len_u32_group;
+///
+/// assert_eq!(encoded_len_u32_group(&[1, 300, 70_000, 16_777_216]), 11);
+/// assert_eq!(encoded_len_u32_group(&[1, 2, 3, 4, 5]), 7);
+/// ```
+pub fn encoded_len_u32_group(values: &[u32]) -> usize {
+    values.len().div_ceil(GROUP_SIZE) + values.iter().map(|&v| byte_len(v)).sum::<usize>()
+}
+
+/// Returns the largest number of bytes `count` values can encode to.
+pub const fn max_encoded_len(count: usize) -> usize {
+    count.div_ceil(GROUP_SIZE) + count * 4
+}
+
+/// Encodes a slice of u32 values in Group Varint format.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::group_varint::encode_u32_group;
+///
+/// assert_eq!(
+///     encode_u32_group(&[1, 300, 70_000, 16_777_216]),
+///     vec![0xE4, 0x01, 0x2C, 0x01, 0x70, 0x11, 0x01, 0x00, 0x00, 0x00, 0x01]
+/// );
+/// ```
+pub fn encode_u32_group(values: &[u32]) -> Vec<u8> {
+    let mut result = vec![0u8; encoded_len_u32_group(values)];
+    encode_u32_group_into(values, &mut result).expect("buffer sized by encoded_len_u32_group");
+    result
+}
+
+/// Encodes a slice of u32 values into a provided buffer, returning the number
+/// of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::group_varint::{encode_u32_group_into, max_encoded_len};
+///
+/// let values = [1, 2, 300];
+/// let mut buf = vec![0u8; max_encoded_len(values.len())];
+/// let n = encode_u32_group_into(&values, &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0x10, 0x01, 0x02, 0x2C, 0x01]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small. Nothing
+/// is written in that case.
+pub fn encode_u32_group_into(values: &[u32], buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let needed = encoded_len_u32_group(values);
+    if buf.len() < needed {
+        return Err(UVarintError::BufferTooSmall { needed });
+    }
+
+    let mut pos = 0;
+
+    for group in values.chunks(GROUP_SIZE) {
+        let tag_pos = pos;
+        let mut tag = 0u8;
+        pos += 1;
+
+        for (i, &value) in group.iter().enumerate() {
+            let n = byte_len(value);
+            tag |= ((n - 1) as u8) << (2 * i);
+            buf[pos..pos + n].copy_from_slice(&value.to_le_bytes()[..n]);
+            pos += n;
+        }
+
+        buf[tag_pos] = tag;
+    }
+
+    Ok(pos)
+}
+
+/// Decodes one group into `out` (at most four values), returning the number of
+/// bytes consumed.
+#[inline]
+fn decode_group(data: &[u8], out: &mut [u32]) -> Result<usize, UVarintError> {
+    let &tag = data.first().ok_or(UVarintError::Incomplete)?;
+    let mut pos = 1;
+
+    for (i, slot) in out.iter_mut().enumerate() {
+        let n = ((tag >> (2 * i)) & 0b11) as usize + 1;
+        let bytes = data.get(pos..pos + n).ok_or(UVarintError::Incomplete)?;
+
+        let mut le = [0u8; 4];
+        le[..n].copy_from_slice(bytes);
+        *slot = u32::from_le_bytes(le);
+        pos += n;
+    }
+
+    Ok(pos)
+}
+
+/// Decodes `out.len()` Group Varint values from a byte slice, returning the
+/// number of bytes consumed.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::group_varint::decode_u32_group;
+///
+/// let data = [0x10, 0x01, 0x02, 0x2C, 0x01];
+/// let mut out = [0u32; 3];
+/// assert_eq!(decode_u32_group(&data, &mut out).unwrap(), 5);
+/// assert_eq!(out, [1, 2, 300]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before `out` is full.
+pub fn decode_u32_group(data: &[u8], out: &mut [u32]) -> Result<usize, UVarintError> {
+    let mut pos = 0;
+
+    for group in out.chunks_mut(GROUP_SIZE) {
+        pos += decode_group(&data[pos..], group)?;
+    }
+
+    Ok(pos)
+}
+
+/// Streaming iterator over `count` Group Varint values.
+///
+/// Values are decoded one group at a time. After the first error the iterator
+/// is exhausted.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::group_varint::{encode_u32_group, iter_u32_group};
+///
+/// let values = [5, 300, 70_000, 1, 2];
+/// let data = encode_u32_group(&values);
+///
+/// let decoded: Result<Vec<u32>, _> = iter_u32_group(&data, values.len()).collect();
+/// assert_eq!(decoded.unwrap(), values);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GroupVarintIter<'a> {
+    data: &'a [u8],
+    remaining: usize,
+    group: [u32; GROUP_SIZE],
+    group_pos: usize,
+    group_len: usize,
+}
+
+impl<'a> GroupVarintIter<'a> {
+    /// Returns the bytes that have not been decoded yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl Iterator for GroupVarintIter<'_> {
+    type Item = Result<u32, UVarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.group_pos == self.group_len {
+            if self.remaining == 0 {
+                return None;
+            }
+
+            let len = self.remaining.min(GROUP_SIZE);
+            match decode_group(self.data, &mut self.group[..len]) {
+                Ok(n) => {
+                    self.data = &self.data[n..];
+                    self.remaining -= len;
+                    self.group_pos = 0;
+                    self.group_len = len;
+                }
+                Err(e) => {
+                    self.data = &[];
+                    self.remaining = 0;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        let value = self.group[self.group_pos];
+        self.group_pos += 1;
+        Some(Ok(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining + (self.group_len - self.group_pos);
+        (len, Some(len))
+    }
+}
+
+impl FusedIterator for GroupVarintIter<'_> {}
+
+/// Returns an iterator over the first `count` Group Varint values in `data`.
+pub fn iter_u32_group(data: &[u8], count: usize) -> GroupVarintIter<'_> {
+    GroupVarintIter {
+        data,
+        remaining: count,
+        group: [0; GROUP_SIZE],
+        group_pos: 0,
+        group_len: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_group() {
+        let values = [1, 300, 70_000, 16_777_216];
+        let encoded = encode_u32_group(&values);
+        assert_eq!(encoded[0], 0b1110_0100);
+
+        let mut out = [0u32; 4];
+        assert_eq!(decode_u32_group(&encoded, &mut out).unwrap(), encoded.len());
+        assert_eq!(out, values);
+    }
+
+    #[test]
+    fn test_partial_groups() {
+        for count in 0..=9 {
+            let values: Vec<u32> = (0..count).map(|i| 1 << (i * 3)).collect();
+            let encoded = encode_u32_group(&values);
+            assert_eq!(encoded.len(), encoded_len_u32_group(&values));
+
+            let mut out = vec![0u32; count];
+            assert_eq!(decode_u32_group(&encoded, &mut out).unwrap(), encoded.len());
+            assert_eq!(out, values);
+        }
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let values = [0, u32::MAX, 0, u32::MAX, 255, 256];
+        let encoded = encode_u32_group(&values);
+        assert!(encoded.len() <= max_encoded_len(values.len()));
+
+        let mut out = [0u32; 6];
+        decode_u32_group(&encoded, &mut out).unwrap();
+        assert_eq!(out, values);
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0xAAu8; 3];
+        assert!(matches!(
+            encode_u32_group_into(&[1, 300], &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 4 })
+        ));
+        assert_eq!(buf, [0xAA; 3]);
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        let encoded = encode_u32_group(&[1, 300, 70_000]);
+        let mut out = [0u32; 3];
+
+        assert!(matches!(
+            decode_u32_group(&[], &mut out),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode_u32_group(&encoded[..encoded.len() - 1], &mut out),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_iter_matches_slice_decode() {
+        let values: Vec<u32> = (0..23).map(|i| i * 7_919 % 1_000_003).collect();
+        let encoded = encode_u32_group(&values);
+
+        let mut iter = iter_u32_group(&encoded, values.len());
+        assert_eq!(iter.size_hint(), (23, Some(23)));
+
+        let decoded: Vec<u32> = iter.by_ref().map(Result::unwrap).collect();
+        assert_eq!(decoded, values);
+        assert!(iter.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_iter_stops_after_error() {
+        let encoded = encode_u32_group(&[1, 2, 3, 4, 5]);
+        let mut iter = iter_u32_group(&encoded[..encoded.len() - 1], 5);
+
+        for expected in 1..=4 {
+            assert_eq!(iter.next().unwrap().unwrap(), expected);
+        }
+        assert!(matches!(iter.next(), Some(Err(UVarintError::Incomplete))));
+        assert!(iter.next().is_none());
+    }
+}
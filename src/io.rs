@@ -1,11 +1,16 @@
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 
 use crate::{
     decode::{decode_u32, decode_u64},
-    encode_u32, encode_u64,
+    encode_u32, encode_u32_into, encode_u64, encode_u64_into, encode_u128_into,
     error::UVarintError,
+    frame::checked_frame_len,
+    zigzag::{zigzag_encode_i32, zigzag_encode_i64, zigzag_encode_i128},
 };
 
+/// Longest varint any writer method produces, a `u128`.
+const MAX_VARINT_LEN: usize = 19;
+
 /// Read a varint-encoded u32 from any `Read` implementation
 ///
 /// # Examples
@@ -87,7 +92,7 @@ pub fn write_u64<W: Write>(writer: &mut W, value: u64) -> Result<usize, UVarintE
     let bytes = encode_u64(value);
     writer
         .write_all(&bytes)
-        .map_err(|_| UVarintError::WriteFailed)?;
+        .map_err(UVarintError::WriteFailed)?;
     Ok(bytes.len())
 }
 
@@ -106,10 +111,88 @@ pub fn write_u32<W: Write>(writer: &mut W, value: u32) -> Result<usize, UVarintE
     let bytes = encode_u32(value);
     writer
         .write_all(&bytes)
-        .map_err(|_| UVarintError::WriteFailed)?;
+        .map_err(UVarintError::WriteFailed)?;
     Ok(bytes.len())
 }
 
+/// Write a varint length prefix followed by `payload` to any `Write`
+/// implementation, returning the total number of bytes written
+///
+/// # Examples
+///
+/// ```rust
+/// use uvarint::io::write_frame;
+///
+/// let mut buf = Vec::new();
+/// write_frame(&mut buf, b"hi").unwrap();
+/// assert_eq!(buf, vec![0x02, b'h', b'i']);
+/// ```
+pub fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> Result<usize, UVarintError> {
+    let n = write_u64(writer, payload.len() as u64)?;
+    writer
+        .write_all(payload)
+        .map_err(UVarintError::WriteFailed)?;
+    Ok(n + payload.len())
+}
+
+/// Read a varint-length-prefixed frame from any `Read` implementation
+///
+/// The length is checked against `max_len` before anything is allocated, so a
+/// corrupted or hostile prefix cannot exhaust memory.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use uvarint::io::read_frame;
+///
+/// let mut cursor = Cursor::new(vec![0x02, b'h', b'i']);
+/// assert_eq!(read_frame(&mut cursor, 1024).unwrap(), b"hi");
+/// ```
+pub fn read_frame<R: Read>(reader: &mut R, max_len: usize) -> Result<Vec<u8>, UVarintError> {
+    let len = checked_frame_len(read_u64(reader)?, max_len)?;
+
+    let mut payload = vec![0u8; len];
+    reader
+        .read_exact(&mut payload)
+        .map_err(|_| UVarintError::Incomplete)?;
+    Ok(payload)
+}
+
+/// Write a varint length prefix followed by the UTF-8 bytes of `s` to any
+/// `Write` implementation, returning the total number of bytes written
+///
+/// # Examples
+///
+/// ```rust
+/// use uvarint::io::write_str;
+///
+/// let mut buf = Vec::new();
+/// write_str(&mut buf, "hi").unwrap();
+/// assert_eq!(buf, vec![0x02, b'h', b'i']);
+/// ```
+pub fn write_str<W: Write>(writer: &mut W, s: &str) -> Result<usize, UVarintError> {
+    write_frame(writer, s.as_bytes())
+}
+
+/// Read a varint-length-prefixed UTF-8 string from any `Read` implementation
+///
+/// Like [`read_frame`], the length is checked against `max_len` before
+/// anything is allocated.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use uvarint::io::read_string;
+///
+/// let mut cursor = Cursor::new(vec![0x02, b'h', b'i']);
+/// assert_eq!(read_string(&mut cursor, 1024).unwrap(), "hi");
+/// ```
+pub fn read_string<R: Read>(reader: &mut R, max_len: usize) -> Result<String, UVarintError> {
+    String::from_utf8(read_frame(reader, max_len)?).map_err(|_| UVarintError::InvalidUtf8)
+}
+
 /// Extension trait for reading varints from `Read` types
 ///
 /// # Examples
@@ -161,6 +244,365 @@ pub trait WriteVarintExt: Write + Sized {
 
 impl<W: Write> WriteVarintExt for W {}
 
+/// A `Read` wrapper that tracks how far into the stream it is and how many
+/// varints it has decoded
+///
+/// Errors from its `read_*` methods are wrapped in `UVarintError::AtOffset`
+/// carrying the offset where the failing varint (or frame) started, so a
+/// corrupted file can be inspected at the exact location.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Cursor;
+/// use uvarint::{UVarintError, io::VarintReader};
+///
+/// let mut reader = VarintReader::new(Cursor::new(vec![0xAC, 0x02, 0x05, 0x80]));
+/// assert_eq!(reader.read_u64().unwrap(), 300);
+/// assert_eq!(reader.read_u64().unwrap(), 5);
+/// assert_eq!((reader.position(), reader.count()), (3, 2));
+///
+/// let err = reader.read_u64().unwrap_err();
+/// assert!(matches!(err, UVarintError::AtOffset { offset: 3, .. }));
+/// ```
+#[derive(Debug)]
+pub struct VarintReader<R> {
+    inner: R,
+    position: u64,
+    count: u64,
+}
+
+impl<R: Read> VarintReader<R> {
+    /// Wraps `inner`, counting offsets from its current position as zero.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            position: 0,
+            count: 0,
+        }
+    }
+
+    /// Bytes consumed through this reader so far, including those read via
+    /// its `Read` impl (and so by [`ReadVarintExt`] methods called on it).
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Varints (and frames) decoded so far by the `read_*` methods. Reads via
+    /// the `Read` impl or [`ReadVarintExt`] advance the position only.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Bytes read through it bypass this wrapper and are not added to
+    /// [`position`](Self::position), so every later offset, including those
+    /// in `UVarintError::AtOffset`, is short by that many bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads a u32 varint; see [`read_u32`].
+    pub fn read_u32(&mut self) -> Result<u32, UVarintError> {
+        self.track(read_u32)
+    }
+
+    /// Reads a u64 varint; see [`read_u64`].
+    pub fn read_u64(&mut self) -> Result<u64, UVarintError> {
+        self.track(read_u64)
+    }
+
+    /// Like [`read_frame`], with errors located at the frame's length prefix.
+    pub fn read_frame(&mut self, max_len: usize) -> Result<Vec<u8>, UVarintError> {
+        self.track(|reader| read_frame(reader, max_len))
+    }
+
+    fn track<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, UVarintError>,
+    ) -> Result<T, UVarintError> {
+        let offset = self.position;
+        let value = read(self).map_err(|source| UVarintError::AtOffset {
+            offset,
+            source: Box::new(source),
+        })?;
+        self.count += 1;
+        Ok(value)
+    }
+}
+
+impl<R: Read> Read for VarintReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+/// A buffered writer that encodes varints straight into an internal buffer and
+/// hands them to the underlying `Write` in large chunks
+///
+/// [`write_u64`] issues one `write_all` per value, which is costly on an
+/// unbuffered sink such as a `File` or `TcpStream`. `VarintWriter` only writes
+/// once its buffer is full, on [`flush`](Write::flush) and in
+/// [`into_inner`](Self::into_inner). Any data still buffered when it is
+/// dropped is flushed, ignoring errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use uvarint::io::VarintWriter;
+///
+/// let mut writer = VarintWriter::new(Vec::new());
+/// writer.write_u64(300).unwrap();
+/// writer.write_i32(-1).unwrap();
+/// writer.write_bytes(b"hi").unwrap();
+/// assert_eq!(writer.bytes_written(), 6);
+///
+/// let buf = writer.into_inner().unwrap();
+/// assert_eq!(buf, vec![0xAC, 0x02, 0x01, 0x02, b'h', b'i']);
+/// ```
+#[derive(Debug)]
+pub struct VarintWriter<W: Write> {
+    // Only `None` once `into_inner` has taken it
+    inner: Option<W>,
+    buf: Box<[u8]>,
+    filled: usize,
+    written: u64,
+}
+
+impl<W: Write> VarintWriter<W> {
+    pub const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY, inner)
+    }
+
+    /// Creates a writer with a buffer of `capacity` bytes, raised to the
+    /// length of the longest varint if smaller.
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            buf: vec![0u8; capacity.max(MAX_VARINT_LEN)].into_boxed_slice(),
+            filled: 0,
+            written: 0,
+        }
+    }
+
+    /// Bytes accepted so far, including those still buffered.
+    pub fn bytes_written(&self) -> u64 {
+        self.written
+    }
+
+    /// The bytes waiting to be written to the underlying writer.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[..self.filled]
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("inner writer taken")
+    }
+
+    /// Writing to the underlying writer directly bypasses the buffer, so
+    /// flush first to keep the output in order.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("inner writer taken")
+    }
+
+    /// Flushes the buffer and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`IntoInnerError`] if the buffered data cannot be written.
+    /// It hands the writer back with whatever was not written still
+    /// buffered, so nothing is lost or written twice.
+    pub fn into_inner(mut self) -> Result<W, IntoInnerError<W>> {
+        if let Err(error) = self.flush_buf() {
+            return Err(IntoInnerError {
+                writer: self,
+                error,
+            });
+        }
+        Ok(self.inner.take().expect("inner writer taken"))
+    }
+
+    pub fn write_u32(&mut self, value: u32) -> Result<usize, UVarintError> {
+        self.encode(5, |buf| encode_u32_into(value, buf))
+    }
+
+    pub fn write_u64(&mut self, value: u64) -> Result<usize, UVarintError> {
+        self.encode(10, |buf| encode_u64_into(value, buf))
+    }
+
+    pub fn write_u128(&mut self, value: u128) -> Result<usize, UVarintError> {
+        self.encode(MAX_VARINT_LEN, |buf| encode_u128_into(value, buf))
+    }
+
+    /// Writes `value` ZigZag-encoded, so small negative numbers stay short.
+    pub fn write_i32(&mut self, value: i32) -> Result<usize, UVarintError> {
+        self.write_u32(zigzag_encode_i32(value))
+    }
+
+    /// Writes `value` ZigZag-encoded, so small negative numbers stay short.
+    pub fn write_i64(&mut self, value: i64) -> Result<usize, UVarintError> {
+        self.write_u64(zigzag_encode_i64(value))
+    }
+
+    /// Writes `value` ZigZag-encoded, so small negative numbers stay short.
+    pub fn write_i128(&mut self, value: i128) -> Result<usize, UVarintError> {
+        self.write_u128(zigzag_encode_i128(value))
+    }
+
+    /// Writes a varint length prefix followed by `bytes`, the same framing as
+    /// [`write_frame`], returning the total number of bytes written.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<usize, UVarintError> {
+        let n = self.write_u64(bytes.len() as u64)?;
+        self.write_all(bytes).map_err(UVarintError::WriteFailed)?;
+        Ok(n + bytes.len())
+    }
+
+    /// Runs `encode` on the free part of the buffer, flushing first unless
+    /// `max_len` bytes are free.
+    fn encode(
+        &mut self,
+        max_len: usize,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, UVarintError>,
+    ) -> Result<usize, UVarintError> {
+        if self.buf.len() - self.filled < max_len {
+            self.flush_buf().map_err(UVarintError::WriteFailed)?;
+        }
+
+        let n = encode(&mut self.buf[self.filled..])?;
+        self.filled += n;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    /// Writes out the buffer. On failure only the unwritten tail is kept, so
+    /// a retry never repeats bytes the sink already accepted.
+    fn flush_buf(&mut self) -> io::Result<()> {
+        let inner = self.inner.as_mut().expect("inner writer taken");
+        let mut done = 0;
+        let mut result = Ok(());
+
+        while done < self.filled {
+            match inner.write(&self.buf[done..self.filled]) {
+                Ok(0) => {
+                    result = Err(io::ErrorKind::WriteZero.into());
+                    break;
+                }
+                Ok(n) => done += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.buf.copy_within(done..self.filled, 0);
+        self.filled -= done;
+        result
+    }
+}
+
+/// The error returned by [`VarintWriter::into_inner`] when the buffered data
+/// cannot be written, carrying the writer so the caller can retry or recover
+/// the underlying sink.
+///
+/// Like [`std::io::IntoInnerError`] it keeps the `io::Error` the sink
+/// returned, so e.g. `WouldBlock` can be told apart from a real failure.
+#[derive(Debug)]
+pub struct IntoInnerError<W: Write> {
+    writer: VarintWriter<W>,
+    error: io::Error,
+}
+
+impl<W: Write> IntoInnerError<W> {
+    /// The error that stopped the buffer from being written.
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Returns the writer, with the unwritten data still buffered.
+    pub fn into_inner(self) -> VarintWriter<W> {
+        self.writer
+    }
+
+    /// Returns the error, dropping the writer and anything still buffered.
+    pub fn into_error(self) -> io::Error {
+        self.error
+    }
+}
+
+impl<W: Write> std::fmt::Display for IntoInnerError<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<W: Write + std::fmt::Debug> std::error::Error for IntoInnerError<W> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<W: Write> From<IntoInnerError<W>> for UVarintError {
+    fn from(err: IntoInnerError<W>) -> Self {
+        UVarintError::WriteFailed(err.into_error())
+    }
+}
+
+impl<W: Write> From<IntoInnerError<W>> for io::Error {
+    fn from(err: IntoInnerError<W>) -> Self {
+        err.into_error()
+    }
+}
+
+impl<W: Write> Write for VarintWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buf.len() - self.filled < data.len() {
+            self.flush_buf()?;
+        }
+
+        // Too large to be worth buffering
+        if data.len() >= self.buf.len() {
+            let n = self.get_mut().write(data)?;
+            self.written += n as u64;
+            return Ok(n);
+        }
+
+        self.buf[self.filled..self.filled + data.len()].copy_from_slice(data);
+        self.filled += data.len();
+        self.written += data.len() as u64;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for VarintWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.flush_buf();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +696,57 @@ mod tests {
         std::fs::remove_file("/tmp/test_varint.bin").unwrap();
     }
 
+    #[test]
+    fn test_frame_roundtrip() {
+        let mut buf = Vec::new();
+        write_frame(&mut buf, b"first").unwrap();
+        write_frame(&mut buf, b"").unwrap();
+        write_frame(&mut buf, &[7u8; 200]).unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(read_frame(&mut cursor, 256).unwrap(), b"first");
+        assert_eq!(read_frame(&mut cursor, 256).unwrap(), b"");
+        assert_eq!(read_frame(&mut cursor, 256).unwrap(), vec![7u8; 200]);
+    }
+
+    #[test]
+    fn test_read_frame_too_large() {
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        assert!(matches!(
+            read_frame(&mut cursor, 1024),
+            Err(UVarintError::FrameTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_frame_truncated_payload() {
+        let mut cursor = Cursor::new(vec![0x05, b'a', b'b']);
+        assert!(matches!(
+            read_frame(&mut cursor, 1024),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let mut buf = Vec::new();
+        write_str(&mut buf, "héllo").unwrap();
+        write_str(&mut buf, "").unwrap();
+
+        let mut cursor = Cursor::new(buf);
+        assert_eq!(read_string(&mut cursor, 64).unwrap(), "héllo");
+        assert_eq!(read_string(&mut cursor, 64).unwrap(), "");
+    }
+
+    #[test]
+    fn test_read_string_invalid_utf8() {
+        let mut cursor = Cursor::new(vec![0x02, 0xC3, 0x28]);
+        assert!(matches!(
+            read_string(&mut cursor, 64),
+            Err(UVarintError::InvalidUtf8)
+        ));
+    }
+
     #[test]
     fn test_read_incomplete() {
         let data = vec![0x80];
@@ -264,4 +757,175 @@ mod tests {
             Err(UVarintError::Incomplete)
         ));
     }
+
+    #[test]
+    fn test_varint_reader_tracks_position() {
+        let mut buf = Vec::new();
+        write_u64(&mut buf, 300).unwrap();
+        write_frame(&mut buf, b"abc").unwrap();
+        write_u32(&mut buf, 7).unwrap();
+
+        let mut reader = VarintReader::new(Cursor::new(buf));
+        assert_eq!(reader.read_u64().unwrap(), 300);
+        assert_eq!(reader.position(), 2);
+        assert_eq!(reader.read_frame(16).unwrap(), b"abc");
+        assert_eq!(reader.position(), 6);
+        assert_eq!(reader.read_u32().unwrap(), 7);
+        assert_eq!((reader.position(), reader.count()), (7, 3));
+
+        // Plain reads are counted as well
+        let mut reader = VarintReader::new(Cursor::new(vec![1, 2, 3, 0x05]));
+        let mut raw = [0u8; 3];
+        reader.read_exact(&mut raw).unwrap();
+        assert_eq!(reader.read_u64().unwrap(), 5);
+        assert_eq!((reader.position(), reader.count()), (4, 1));
+    }
+
+    #[test]
+    fn test_varint_reader_error_offset() {
+        let mut reader = VarintReader::new(Cursor::new(vec![0x01, 0x02, 0xFF, 0xFF]));
+        reader.read_u64().unwrap();
+        reader.read_u64().unwrap();
+
+        let err = reader.read_u64().unwrap_err();
+        assert_eq!(err.to_string(), "At offset 2: Incomplete varint data");
+        assert!(matches!(
+            err,
+            UVarintError::AtOffset { offset: 2, source } if matches!(*source, UVarintError::Incomplete)
+        ));
+        assert_eq!(reader.count(), 2);
+
+        let mut reader = VarintReader::new(Cursor::new(vec![0x00, 0x05, b'a']));
+        reader.read_u64().unwrap();
+        assert!(matches!(
+            reader.read_frame(16),
+            Err(UVarintError::AtOffset { offset: 1, .. })
+        ));
+    }
+
+    /// Records the size of every `write` call it receives, accepting at most
+    /// `limit` bytes in total if set.
+    #[derive(Debug, Default)]
+    struct CountingSink {
+        data: Vec<u8>,
+        writes: Vec<usize>,
+        limit: Option<usize>,
+    }
+
+    impl Write for CountingSink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let room = self.limit.map_or(buf.len(), |l| l - self.data.len());
+            if room == 0 {
+                return Err(io::ErrorKind::StorageFull.into());
+            }
+
+            let n = buf.len().min(room);
+            self.data.extend_from_slice(&buf[..n]);
+            self.writes.push(n);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_varint_writer_matches_unbuffered() {
+        let mut expected = Vec::new();
+        let mut writer = VarintWriter::with_capacity(32, Vec::new());
+
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            write_u64(&mut expected, value).unwrap();
+            writer.write_u64(value).unwrap();
+        }
+        expected.extend(crate::encode_u128(u128::MAX));
+        writer.write_u128(u128::MAX).unwrap();
+        expected.extend(crate::encode_u64(zigzag_encode_i64(-300)));
+        writer.write_i64(-300).unwrap();
+        write_frame(&mut expected, &[7u8; 100]).unwrap();
+        writer.write_bytes(&[7u8; 100]).unwrap();
+
+        assert_eq!(writer.bytes_written(), expected.len() as u64);
+        assert_eq!(writer.into_inner().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_varint_writer_batches_writes() {
+        let mut writer = VarintWriter::with_capacity(64, CountingSink::default());
+        for value in 0..100u64 {
+            writer.write_u64(value).unwrap();
+        }
+        assert!(writer.get_ref().writes.iter().all(|&n| n > MAX_VARINT_LEN));
+
+        writer.flush().unwrap();
+        assert!(writer.buffer().is_empty());
+
+        let sink = writer.into_inner().unwrap();
+        assert_eq!(sink.data, (0..100).collect::<Vec<u8>>());
+        assert!(sink.writes.len() <= 3);
+    }
+
+    #[test]
+    fn test_varint_writer_flushes_on_drop() {
+        let mut out = Vec::new();
+        {
+            let mut writer = VarintWriter::new(&mut out);
+            writer.write_u32(300).unwrap();
+        }
+        assert_eq!(out, vec![0xAC, 0x02]);
+    }
+
+    #[test]
+    fn test_varint_writer_write_failed() {
+        let mut sink = CountingSink {
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        let mut writer = VarintWriter::with_capacity(0, &mut sink);
+        writer.write_u64(300).unwrap();
+        assert_eq!(writer.buffer(), &[0xAC, 0x02]);
+
+        // The sink takes one byte; the rest stays buffered
+        let err = writer.into_inner().unwrap_err();
+        assert_eq!(err.error().kind(), io::ErrorKind::StorageFull);
+        let mut writer = err.into_inner();
+        assert_eq!(writer.buffer(), &[0x02]);
+
+        // Retrying writes only what is left, and dropping does not repeat it
+        writer.get_mut().limit = None;
+        writer.into_inner().unwrap();
+        assert_eq!(sink.data, vec![0xAC, 0x02]);
+        assert_eq!(sink.writes, vec![1, 1]);
+
+        // A failed writer dropped without recovery does not write twice either
+        let mut sink = CountingSink {
+            limit: Some(1),
+            ..Default::default()
+        };
+        let mut writer = VarintWriter::new(&mut sink);
+        writer.write_u64(300).unwrap();
+        let err = UVarintError::from(writer.into_inner().unwrap_err());
+        assert!(matches!(
+            &err,
+            UVarintError::WriteFailed(e) if e.kind() == io::ErrorKind::StorageFull
+        ));
+        assert_eq!(sink.data, vec![0xAC]);
+        assert_eq!(sink.writes, vec![1]);
+
+        // Errors from the write methods keep the sink's error too
+        let mut writer = VarintWriter::with_capacity(
+            0,
+            CountingSink {
+                limit: Some(0),
+                ..Default::default()
+            },
+        );
+        writer.write_u64(1).unwrap();
+        assert!(matches!(
+            writer.write_u128(1),
+            Err(UVarintError::WriteFailed(e)) if e.kind() == io::ErrorKind::StorageFull
+        ));
+    }
 }
@@ -0,0 +1,127 @@
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    error::UVarintError,
+};
+
+/// Slice decoder shared by every format, returning `(bytes_consumed, value)`.
+pub(crate) type DecodeFn<T> = fn(&[u8]) -> Result<(usize, T), UVarintError>;
+
+/// Zero-allocation iterator over a sequence of concatenated varints.
+///
+/// Each item is decoded lazily from the borrowed slice. After the first error
+/// the iterator is exhausted, since the position of the next value is unknown.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::iter_u64;
+///
+/// let data = [0xAC, 0x02, 0xFF, 0x01, 0x05];
+/// let values: Result<Vec<u64>, _> = iter_u64(&data).collect();
+/// assert_eq!(values.unwrap(), vec![300, 255, 5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VarintIter<'a, T> {
+    data: &'a [u8],
+    decode: DecodeFn<T>,
+}
+
+impl<'a, T> VarintIter<'a, T> {
+    /// Creates an iterator that decodes `data` with `decode` until it is
+    /// exhausted.
+    pub(crate) fn new(data: &'a [u8], decode: DecodeFn<T>) -> Self {
+        VarintIter { data, decode }
+    }
+
+    /// Returns the bytes that have not been decoded yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Exhausts the iterator without decoding the remaining bytes.
+    pub(crate) fn finish(&mut self) {
+        self.data = &[];
+    }
+}
+
+impl<T> Iterator for VarintIter<'_, T> {
+    type Item = Result<T, UVarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        match (self.decode)(self.data) {
+            Ok((n, value)) => {
+                self.data = &self.data[n..];
+                Some(Ok(value))
+            }
+            Err(e) => {
+                self.finish();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<T> std::iter::FusedIterator for VarintIter<'_, T> {}
+
+/// Returns an iterator over the varint-encoded u32 values in `data`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::iter_u32;
+///
+/// let mut iter = iter_u32(&[0x01, 0xAC, 0x02]);
+/// assert_eq!(iter.next().unwrap().unwrap(), 1);
+/// assert_eq!(iter.next().unwrap().unwrap(), 300);
+/// assert!(iter.next().is_none());
+/// ```
+pub fn iter_u32(data: &[u8]) -> VarintIter<'_, u32> {
+    VarintIter::new(data, decode_u32)
+}
+
+/// Returns an iterator over the varint-encoded u64 values in `data`.
+pub fn iter_u64(data: &[u8]) -> VarintIter<'_, u64> {
+    VarintIter::new(data, decode_u64)
+}
+
+/// Returns an iterator over the varint-encoded u128 values in `data`.
+pub fn iter_u128(data: &[u8]) -> VarintIter<'_, u128> {
+    VarintIter::new(data, decode_u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_multiple_values() {
+        let data = [
+            0xAC, 0x02, // 300
+            0x7F, // 127
+            0x80, 0x01, // 128
+        ];
+
+        let values: Vec<u32> = iter_u32(&data).map(Result::unwrap).collect();
+        assert_eq!(values, vec![300, 127, 128]);
+    }
+
+    #[test]
+    fn test_iter_empty() {
+        assert!(iter_u64(&[]).next().is_none());
+    }
+
+    #[test]
+    fn test_iter_stops_after_error() {
+        let data = [0x05, 0x80];
+        let mut iter = iter_u64(&data);
+
+        assert_eq!(iter.next().unwrap().unwrap(), 5);
+        assert_eq!(iter.remaining(), &[0x80]);
+        assert!(matches!(iter.next(), Some(Err(UVarintError::Incomplete))));
+        assert!(iter.next().is_none());
+    }
+}
@@ -1,10 +1,55 @@
+pub mod block;
+#[cfg(feature = "bytes")]
+pub mod buf;
+#[cfg(feature = "tokio-util")]
+pub mod codec;
+pub mod compact_size;
 mod decode;
+mod delta;
 mod encode;
 mod error;
+mod explain;
+mod frame;
+pub mod git;
+pub mod group_varint;
 pub mod io;
+mod iter;
+pub mod prefix;
+pub mod record;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod slice;
+pub mod sqlite;
+pub mod stream_vbyte;
+mod string;
+pub mod timeseries;
+mod unchecked;
+pub mod vlq;
+mod zigzag;
 
 pub use decode::{decode_u32, decode_u64, decode_u128};
+pub use delta::{
+    DeltaIter, decode_delta_u32, decode_delta_u64, encode_delta_u32, encode_delta_u64,
+    iter_delta_u32, iter_delta_u64,
+};
 pub use encode::{
     encode_u32, encode_u32_into, encode_u64, encode_u64_into, encode_u128, encode_u128_into,
+    encoded_len_u32, encoded_len_u64, encoded_len_u128,
 };
 pub use error::UVarintError;
+pub use explain::{ByteStep, Explanation, explain_u64};
+pub use frame::{decode_frame, encode_frame, encode_frame_into, encoded_frame_len};
+pub use iter::{VarintIter, iter_u32, iter_u64, iter_u128};
+pub use record::{VarintDecode, VarintEncode};
+pub use slice::{SliceReader, SliceWriter};
+pub use string::{decode_bytes, decode_str, encode_bytes_into, encode_str_into};
+pub use unchecked::{
+    ValidatedU64Iter, ValidatedU64Stream, decode_u32_unchecked, decode_u64_unchecked,
+    validate_u64_stream,
+};
+#[cfg(feature = "derive")]
+pub use uvarint_derive::{VarintDecode, VarintEncode};
+pub use zigzag::{
+    zigzag_decode_i32, zigzag_decode_i64, zigzag_decode_i128, zigzag_encode_i32, zigzag_encode_i64,
+    zigzag_encode_i128,
+};
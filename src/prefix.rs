@@ -0,0 +1,315 @@
+//! PrefixVarint: length-prefixed variable-length integers.
+//!
+//! # Encoding Format
+//!
+//! Instead of a continuation bit in every byte, PrefixVarint puts the total
+//! length in the first byte as a run of trailing 1-bits, terminated by a 0-bit.
+//! The value is stored little-endian in the remaining bits:
+//!
+//! ```text
+//! xxxx_xxx0                          1 byte,  7 value bits
+//! xxxx_xx01 xxxx_xxxx                2 bytes, 14 value bits
+//! xxxx_x011 xxxx_xxxx xxxx_xxxx      3 bytes, 21 value bits
+//! ...
+//! 0111_1111 (7 bytes)                8 bytes, 56 value bits
+//! 1111_1111 (8 bytes)                9 bytes, full 64-bit value
+//! ```
+//!
+//! A decoder learns the length from `first_byte.trailing_ones()` without
+//! looking at any other byte, then extracts the value with a single unaligned
+//! load and shift. Up to 56 bits the encoded length matches LEB128, so
+//! [`encoded_len_u64`] agrees with [`crate::encoded_len_u64`] in that range.
+//!
+//! # Examples
+//!
+//! ```
+//! use uvarint::prefix;
+//!
+//! // 300 = 0b1_0010_1100, needs 2 bytes
+//! // (300 << 2) | 0b01 = 0b100_1011_0001 → [0xB1, 0x04]
+//! assert_eq!(prefix::encode_u64(300), vec![0xB1, 0x04]);
+//! assert_eq!(prefix::decode_u64(&[0xB1, 0x04]).unwrap(), (2, 300));
+//! ```
+
+use crate::{error::UVarintError, iter::VarintIter};
+
+/// Maximum encoded length of a u64.
+const MAX_LEN: usize = 9;
+
+/// Largest value that fits in the 8-byte form.
+const MAX_8_BYTE: u64 = (1 << 56) - 1;
+
+/// Returns the number of bytes `encode_u32` would produce for `value`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::encoded_len_u32;
+///
+/// assert_eq!(encoded_len_u32(127), 1);
+/// assert_eq!(encoded_len_u32(u32::MAX), 5);
+/// ```
+pub const fn encoded_len_u32(value: u32) -> usize {
+    encoded_len_u64(value as u64)
+}
+
+/// Returns the number of bytes `encode_u64` would produce for `value`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::encoded_len_u64;
+///
+/// assert_eq!(encoded_len_u64(0), 1);
+/// assert_eq!(encoded_len_u64(128), 2);
+/// assert_eq!(encoded_len_u64((1 << 56) - 1), 8);
+/// assert_eq!(encoded_len_u64(u64::MAX), 9);
+/// ```
+pub const fn encoded_len_u64(value: u64) -> usize {
+    if value > MAX_8_BYTE {
+        return MAX_LEN;
+    }
+
+    crate::encode::encoded_len_u64(value)
+}
+
+/// Encodes a u32 value into PrefixVarint format.
+pub fn encode_u32(value: u32) -> Vec<u8> {
+    encode_u64(value as u64)
+}
+
+/// Encodes a u32 into a provided buffer, returning the number of bytes written.
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u32_into(value: u32, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    encode_u64_into(value as u64, buf)
+}
+
+/// Encodes a u64 value into PrefixVarint format.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::encode_u64;
+///
+/// assert_eq!(encode_u64(5), vec![0x0A]);
+/// assert_eq!(encode_u64(300), vec![0xB1, 0x04]);
+/// ```
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    let mut buf = [0u8; MAX_LEN];
+    let n = encode_u64_into(value, &mut buf).expect("MAX_LEN fits any u64");
+    buf[..n].to_vec()
+}
+
+/// Encodes a u64 into a provided buffer, returning the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::encode_u64_into;
+///
+/// let mut buf = [0u8; 9];
+/// let n = encode_u64_into(300, &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0xB1, 0x04]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u64_into(value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let len = encoded_len_u64(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    if len == MAX_LEN {
+        buf[0] = 0xFF;
+        buf[1..MAX_LEN].copy_from_slice(&value.to_le_bytes());
+        return Ok(MAX_LEN);
+    }
+
+    // `len - 1` one-bits, then a zero-bit, then the value
+    let tag = (1u64 << (len - 1)) - 1;
+    let raw = (value << len) | tag;
+    buf[..len].copy_from_slice(&raw.to_le_bytes()[..len]);
+
+    Ok(len)
+}
+
+/// Decodes a PrefixVarint-encoded u32 from a byte slice.
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the value does,
+/// or `UVarintError::Overflow` if the value does not fit in a u32.
+pub fn decode_u32(data: &[u8]) -> Result<(usize, u32), UVarintError> {
+    let (n, value) = decode_u64(data)?;
+    let value = u32::try_from(value).map_err(|_| UVarintError::Overflow)?;
+    Ok((n, value))
+}
+
+/// Decodes a PrefixVarint-encoded u64 from a byte slice.
+///
+/// When at least 8 bytes are available the value is read with one unaligned
+/// load; shorter tails are copied into a zero-padded buffer first.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::decode_u64;
+///
+/// assert_eq!(decode_u64(&[0x0A]).unwrap(), (1, 5));
+/// assert_eq!(decode_u64(&[0xB1, 0x04, 0xFF]).unwrap(), (2, 300));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the value does.
+#[inline]
+pub fn decode_u64(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    let &first = data.first().ok_or(UVarintError::Incomplete)?;
+    let len = first.trailing_ones() as usize + 1;
+
+    if data.len() < len {
+        return Err(UVarintError::Incomplete);
+    }
+
+    if len == MAX_LEN {
+        let mut le = [0u8; 8];
+        le.copy_from_slice(&data[1..MAX_LEN]);
+        return Ok((MAX_LEN, u64::from_le_bytes(le)));
+    }
+
+    let raw = match data.get(..8) {
+        Some(word) => {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(word);
+            u64::from_le_bytes(le)
+        }
+        None => {
+            let mut le = [0u8; 8];
+            le[..data.len()].copy_from_slice(data);
+            u64::from_le_bytes(le)
+        }
+    };
+
+    // Drop the bytes that belong to the next value, then the length tag
+    let mask = u64::MAX >> (64 - 8 * len);
+    Ok((len, (raw & mask) >> len))
+}
+
+/// Returns an iterator over the PrefixVarint-encoded u32 values in `data`.
+pub fn iter_u32(data: &[u8]) -> VarintIter<'_, u32> {
+    VarintIter::new(data, decode_u32)
+}
+
+/// Returns an iterator over the PrefixVarint-encoded u64 values in `data`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::prefix::iter_u64;
+///
+/// let data = [0xB1, 0x04, 0x0A];
+/// let values: Result<Vec<u64>, _> = iter_u64(&data).collect();
+/// assert_eq!(values.unwrap(), vec![300, 5]);
+/// ```
+pub fn iter_u64(data: &[u8]) -> VarintIter<'_, u64> {
+    VarintIter::new(data, decode_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: [u64; 12] = [
+        0,
+        1,
+        127,
+        128,
+        300,
+        16_383,
+        16_384,
+        u32::MAX as u64,
+        MAX_8_BYTE - 1,
+        MAX_8_BYTE,
+        MAX_8_BYTE + 1,
+        u64::MAX,
+    ];
+
+    #[test]
+    fn test_roundtrip() {
+        for &value in &VALUES {
+            let encoded = encode_u64(value);
+            assert_eq!(encoded.len(), encoded_len_u64(value));
+            assert_eq!(decode_u64(&encoded).unwrap(), (encoded.len(), value));
+        }
+    }
+
+    #[test]
+    fn test_length_tag() {
+        for &value in &VALUES {
+            let encoded = encode_u64(value);
+            assert_eq!(encoded[0].trailing_ones() as usize + 1, encoded.len());
+        }
+    }
+
+    #[test]
+    fn test_encoded_len_matches_leb128() {
+        for &value in VALUES.iter().filter(|&&v| v <= MAX_8_BYTE) {
+            assert_eq!(encoded_len_u64(value), crate::encoded_len_u64(value));
+        }
+    }
+
+    #[test]
+    fn test_decode_ignores_following_bytes() {
+        // A full 8-byte load must not leak the next value's bits
+        let mut data = encode_u64(300);
+        data.extend_from_slice(&[0xFF; 8]);
+        assert_eq!(decode_u64(&data).unwrap(), (2, 300));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(matches!(decode_u64(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(decode_u64(&[0x01]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            decode_u64(&[0xFF, 0x00, 0x00]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_u32() {
+        let encoded = encode_u32(u32::MAX);
+        assert_eq!(decode_u32(&encoded).unwrap(), (5, u32::MAX));
+        assert!(matches!(
+            decode_u32(&encode_u64(u32::MAX as u64 + 1)),
+            Err(UVarintError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0u8; 1];
+        assert!(matches!(
+            encode_u64_into(300, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut data = Vec::new();
+        for &value in &VALUES {
+            data.extend(encode_u64(value));
+        }
+
+        let decoded: Vec<u64> = iter_u64(&data).map(Result::unwrap).collect();
+        assert_eq!(decoded, VALUES);
+    }
+}
@@ -0,0 +1,646 @@
+//! Field-by-field record encoding, the runtime half of `#[derive(VarintEncode,
+//! VarintDecode)]`.
+//!
+//! The derives are enabled with the `derive` feature and implement
+//! [`VarintEncode`] and [`VarintDecode`] by encoding each field in declaration
+//! order. Hand-written impls for the building blocks live here.
+//!
+//! # Encoding Format
+//!
+//! ```text
+//! u16 .. u128, usize     LEB128 varint
+//! i16 .. i128, isize     two's complement bits as a LEB128 varint
+//! u8, i8                 one raw byte
+//! bool                   one byte, 0 or 1
+//! f32, f64               little-endian IEEE 754
+//! String                 [len varint][UTF-8 bytes]
+//! Vec<T>                 [count varint][elements]
+//! Option<T>              0, or 1 followed by T
+//! struct                 fields in order
+//! enum                   [variant index varint][fields in order]
+//! ```
+//!
+//! Plain signed integers are deliberately *not* ZigZag-encoded: their two's
+//! complement bits are written as an unsigned varint, so `-1i64` takes ten
+//! bytes. This differs from the rest of the crate, where signed values are
+//! ZigZag-encoded ([`SliceWriter::write_i64`](crate::SliceWriter::write_i64),
+//! the `serde` format and the `bytes` extension traits), so a derived record
+//! is not byte-compatible with the same struct serialized through `serde`.
+//! Fields that can be negative should use `#[varint(zigzag)]` (see
+//! [`ZigZag`]), which matches those encodings.
+//!
+//! `#[varint(fixed)]` (see [`Fixed`]) stores an integer as little-endian bytes
+//! of its full width, and `#[varint(skip)]` leaves a field out entirely,
+//! filling it with `Default::default()` on decode.
+//!
+//! A `Vec<T>` count comes from the input, so decoding rejects counts above
+//! [`DEFAULT_MAX_VEC_LEN`] (see [`decode_vec_with_max`]) and counts of
+//! non-empty elements that the remaining bytes cannot hold.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use uvarint::{VarintDecode, VarintEncode};
+//!
+//! #[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+//! struct Sample {
+//!     id: u64,
+//!     #[varint(zigzag)]
+//!     delta: i32,
+//!     #[varint(fixed)]
+//!     checksum: u32,
+//!     tags: Vec<String>,
+//!     #[varint(skip)]
+//!     cached: Option<u64>,
+//! }
+//!
+//! let sample = Sample {
+//!     id: 300,
+//!     delta: -1,
+//!     checksum: 0xDEAD_BEEF,
+//!     tags: vec!["a".into()],
+//!     cached: Some(7),
+//! };
+//!
+//! let bytes = sample.encode();
+//! assert_eq!(bytes, [0xAC, 0x02, 0x01, 0xEF, 0xBE, 0xAD, 0xDE, 0x01, 0x01, b'a']);
+//!
+//! let (n, decoded) = Sample::decode(&bytes).unwrap();
+//! assert_eq!(n, bytes.len());
+//! assert_eq!(decoded, Sample { cached: None, ..sample });
+//! # }
+//! ```
+
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    encode::{
+        encode_u32_into, encode_u64_into, encode_u128_into, encoded_len_u32, encoded_len_u64,
+        encoded_len_u128,
+    },
+    error::UVarintError,
+    frame::encoded_frame_len,
+    string::{decode_str, encode_str_into},
+    zigzag::{zigzag_decode_i64, zigzag_decode_i128, zigzag_encode_i64, zigzag_encode_i128},
+};
+
+/// Types that can be written as a sequence of varint-encoded fields.
+///
+/// The impls for `i16` through `i128` write two's complement bits as an
+/// unsigned varint rather than ZigZag-encoding them, so negative values take
+/// the maximum length; wrap them in [`ZigZag`] for the encoding the rest of
+/// the crate uses. See the [module docs](self) for the full format.
+pub trait VarintEncode {
+    /// Returns the number of bytes [`encode_into`](Self::encode_into) writes.
+    fn encoded_len(&self) -> usize;
+
+    /// Writes `self` into a provided buffer, returning the number of bytes
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::BufferTooSmall` if the encoding does not fit.
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError>;
+
+    /// Encodes `self` into a new vector.
+    fn encode(&self) -> Vec<u8> {
+        let mut result = vec![0u8; self.encoded_len()];
+        let n = self
+            .encode_into(&mut result)
+            .expect("buffer sized by encoded_len");
+        result.truncate(n);
+        result
+    }
+}
+
+/// Types that can be read back from the output of [`VarintEncode`].
+pub trait VarintDecode: Sized {
+    /// Decodes a value from the start of `data`, returning the bytes consumed
+    /// and the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Incomplete` if `data` ends early, or another
+    /// variant if the bytes are not valid for the type. Derived impls wrap the
+    /// error in `UVarintError::Field` naming the field that failed.
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError>;
+
+    /// The fewest bytes any encoding of `Self` takes, used to reject element
+    /// counts the input cannot possibly hold. Zero, the default, is always
+    /// correct; it is only too low for types that never decode from nothing.
+    const MIN_ENCODED_LEN: usize = 0;
+}
+
+/// Largest `Vec<T>` the [`VarintDecode`] impl accepts. Elements such as unit
+/// structs take no bytes, so the count alone cannot be trusted.
+pub const DEFAULT_MAX_VEC_LEN: usize = 1 << 24;
+
+/// Encodes a signed integer with ZigZag before LEB128, so small negative
+/// values stay small. Used by `#[varint(zigzag)]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ZigZag<T>(pub T);
+
+/// Encodes an integer as little-endian bytes of its full width. Used by
+/// `#[varint(fixed)]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<T>(pub T);
+
+/// Checks `buf` can hold `len` bytes before anything is written.
+fn reserve(buf: &mut [u8], len: usize) -> Result<&mut [u8], UVarintError> {
+    buf.get_mut(..len)
+        .ok_or(UVarintError::BufferTooSmall { needed: len })
+}
+
+/// Splits the first `len` bytes off `data`.
+fn take(data: &[u8], len: usize) -> Result<&[u8], UVarintError> {
+    data.get(..len).ok_or(UVarintError::Incomplete)
+}
+
+macro_rules! impl_varint {
+    ($($ty:ty => $wide:ty, $encode:ident, $encoded_len:ident, $decode:ident;)*) => {$(
+        impl VarintEncode for $ty {
+            fn encoded_len(&self) -> usize {
+                $encoded_len(*self as $wide)
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                $encode(*self as $wide, buf)
+            }
+        }
+
+        impl VarintDecode for $ty {
+            const MIN_ENCODED_LEN: usize = 1;
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                let (n, value) = $decode(data)?;
+                let value = <$ty>::try_from(value).map_err(|_| UVarintError::Overflow)?;
+                Ok((n, value))
+            }
+        }
+    )*};
+}
+
+impl_varint! {
+    u16 => u32, encode_u32_into, encoded_len_u32, decode_u32;
+    u32 => u32, encode_u32_into, encoded_len_u32, decode_u32;
+    u64 => u64, encode_u64_into, encoded_len_u64, decode_u64;
+    u128 => u128, encode_u128_into, encoded_len_u128, decode_u128;
+    usize => u64, encode_u64_into, encoded_len_u64, decode_u64;
+}
+
+macro_rules! impl_signed {
+    ($($ty:ty => $unsigned:ty;)*) => {$(
+        impl VarintEncode for $ty {
+            fn encoded_len(&self) -> usize {
+                (*self as $unsigned).encoded_len()
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                (*self as $unsigned).encode_into(buf)
+            }
+        }
+
+        impl VarintDecode for $ty {
+            const MIN_ENCODED_LEN: usize = 1;
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                let (n, value) = <$unsigned>::decode(data)?;
+                Ok((n, value as $ty))
+            }
+        }
+    )*};
+}
+
+impl_signed! {
+    i16 => u16;
+    i32 => u32;
+    i64 => u64;
+    i128 => u128;
+    isize => usize;
+}
+
+macro_rules! impl_zigzag {
+    ($($ty:ty => $wide:ty, $unsigned:ty, $zigzag_encode:ident, $zigzag_decode:ident;)*) => {$(
+        impl VarintEncode for ZigZag<$ty> {
+            fn encoded_len(&self) -> usize {
+                $zigzag_encode(self.0 as $wide).encoded_len()
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                $zigzag_encode(self.0 as $wide).encode_into(buf)
+            }
+        }
+
+        impl VarintDecode for ZigZag<$ty> {
+            const MIN_ENCODED_LEN: usize = 1;
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                let (n, value) = <$unsigned>::decode(data)?;
+                let value = <$ty>::try_from($zigzag_decode(value))
+                    .map_err(|_| UVarintError::Overflow)?;
+                Ok((n, ZigZag(value)))
+            }
+        }
+    )*};
+}
+
+impl_zigzag! {
+    i8 => i64, u64, zigzag_encode_i64, zigzag_decode_i64;
+    i16 => i64, u64, zigzag_encode_i64, zigzag_decode_i64;
+    i32 => i64, u64, zigzag_encode_i64, zigzag_decode_i64;
+    i64 => i64, u64, zigzag_encode_i64, zigzag_decode_i64;
+    i128 => i128, u128, zigzag_encode_i128, zigzag_decode_i128;
+    isize => i64, u64, zigzag_encode_i64, zigzag_decode_i64;
+}
+
+macro_rules! impl_fixed {
+    ($($ty:ty),*) => {$(
+        impl VarintEncode for Fixed<$ty> {
+            fn encoded_len(&self) -> usize {
+                size_of::<$ty>()
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                let bytes = self.0.to_le_bytes();
+                reserve(buf, bytes.len())?.copy_from_slice(&bytes);
+                Ok(bytes.len())
+            }
+        }
+
+        impl VarintDecode for Fixed<$ty> {
+            const MIN_ENCODED_LEN: usize = size_of::<$ty>();
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                let bytes = take(data, size_of::<$ty>())?;
+                let value = <$ty>::from_le_bytes(bytes.try_into().expect("took full width"));
+                Ok((bytes.len(), Fixed(value)))
+            }
+        }
+    )*};
+}
+
+impl_fixed!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+macro_rules! impl_raw {
+    ($($ty:ty),*) => {$(
+        impl VarintEncode for $ty {
+            fn encoded_len(&self) -> usize {
+                Fixed(*self).encoded_len()
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                Fixed(*self).encode_into(buf)
+            }
+        }
+
+        impl VarintDecode for $ty {
+            const MIN_ENCODED_LEN: usize = size_of::<$ty>();
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                Fixed::<$ty>::decode(data).map(|(n, value)| (n, value.0))
+            }
+        }
+    )*};
+}
+
+impl_raw!(u8, i8);
+
+macro_rules! impl_float {
+    ($($ty:ty),*) => {$(
+        impl VarintEncode for $ty {
+            fn encoded_len(&self) -> usize {
+                size_of::<$ty>()
+            }
+
+            fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+                let bytes = self.to_le_bytes();
+                reserve(buf, bytes.len())?.copy_from_slice(&bytes);
+                Ok(bytes.len())
+            }
+        }
+
+        impl VarintDecode for $ty {
+            const MIN_ENCODED_LEN: usize = size_of::<$ty>();
+
+            fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                let bytes = take(data, size_of::<$ty>())?;
+                let value = <$ty>::from_le_bytes(bytes.try_into().expect("took full width"));
+                Ok((bytes.len(), value))
+            }
+        }
+    )*};
+}
+
+impl_float!(f32, f64);
+
+impl VarintEncode for bool {
+    fn encoded_len(&self) -> usize {
+        1
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        (*self as u8).encode_into(buf)
+    }
+}
+
+impl VarintDecode for bool {
+    const MIN_ENCODED_LEN: usize = 1;
+
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+        match u8::decode(data)? {
+            (n, 0) => Ok((n, false)),
+            (n, 1) => Ok((n, true)),
+            (_, byte) => Err(UVarintError::InvalidTag(byte)),
+        }
+    }
+}
+
+impl VarintEncode for str {
+    fn encoded_len(&self) -> usize {
+        encoded_frame_len(self.len())
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        encode_str_into(self, buf)
+    }
+}
+
+impl VarintEncode for String {
+    fn encoded_len(&self) -> usize {
+        self.as_str().encoded_len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        self.as_str().encode_into(buf)
+    }
+}
+
+impl VarintDecode for String {
+    const MIN_ENCODED_LEN: usize = 1;
+
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+        decode_str(data).map(|(n, s)| (n, s.to_owned()))
+    }
+}
+
+impl<T: VarintEncode> VarintEncode for [T] {
+    fn encoded_len(&self) -> usize {
+        let items: usize = self.iter().map(VarintEncode::encoded_len).sum();
+        self.len().encoded_len() + items
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        let buf = reserve(buf, self.encoded_len())?;
+
+        let mut pos = self.len().encode_into(buf)?;
+        for item in self {
+            pos += item.encode_into(&mut buf[pos..])?;
+        }
+
+        Ok(pos)
+    }
+}
+
+impl<T: VarintEncode> VarintEncode for Vec<T> {
+    fn encoded_len(&self) -> usize {
+        self.as_slice().encoded_len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        self.as_slice().encode_into(buf)
+    }
+}
+
+impl<T: VarintDecode> VarintDecode for Vec<T> {
+    const MIN_ENCODED_LEN: usize = 1;
+
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+        decode_vec_with_max(data, DEFAULT_MAX_VEC_LEN)
+    }
+}
+
+/// Decodes a `Vec<T>` like its [`VarintDecode`] impl, rejecting more than
+/// `max_count` elements before any are decoded.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::{UVarintError, VarintEncode, record::decode_vec_with_max};
+///
+/// let encoded = vec![1u32, 2, 3].encode();
+/// assert_eq!(decode_vec_with_max::<u32>(&encoded, 3).unwrap(), (4, vec![1, 2, 3]));
+/// assert!(matches!(
+///     decode_vec_with_max::<u32>(&encoded, 2),
+///     Err(UVarintError::SequenceTooLong { count: 3, max: 2 })
+/// ));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::SequenceTooLong` if the count exceeds `max_count`,
+/// `UVarintError::Incomplete` if the input is too short to hold that many
+/// elements, or any error decoding an element returns.
+pub fn decode_vec_with_max<T: VarintDecode>(
+    data: &[u8],
+    max_count: usize,
+) -> Result<(usize, Vec<T>), UVarintError> {
+    let (mut pos, count) = u64::decode(data)?;
+    let count = match usize::try_from(count) {
+        Ok(count) if count <= max_count => count,
+        _ => {
+            return Err(UVarintError::SequenceTooLong {
+                count,
+                max: max_count,
+            });
+        }
+    };
+
+    if T::MIN_ENCODED_LEN > 0 && count > (data.len() - pos) / T::MIN_ENCODED_LEN {
+        return Err(UVarintError::Incomplete);
+    }
+
+    // Zero-length elements can still claim up to `max_count`, so cap the
+    // up-front allocation by the input size as well
+    let mut result = Vec::with_capacity(count.min(data.len() - pos));
+    for _ in 0..count {
+        let (n, item) = T::decode(&data[pos..])?;
+        pos += n;
+        result.push(item);
+    }
+
+    Ok((pos, result))
+}
+
+impl<T: VarintEncode> VarintEncode for Option<T> {
+    fn encoded_len(&self) -> usize {
+        1 + self.as_ref().map_or(0, VarintEncode::encoded_len)
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        let buf = reserve(buf, self.encoded_len())?;
+
+        match self {
+            None => 0u8.encode_into(buf),
+            Some(value) => {
+                1u8.encode_into(buf)?;
+                Ok(1 + value.encode_into(&mut buf[1..])?)
+            }
+        }
+    }
+}
+
+impl<T: VarintDecode> VarintDecode for Option<T> {
+    const MIN_ENCODED_LEN: usize = 1;
+
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+        match u8::decode(data)? {
+            (n, 0) => Ok((n, None)),
+            (n, 1) => {
+                let (m, value) = T::decode(&data[n..])?;
+                Ok((n + m, Some(value)))
+            }
+            (_, tag) => Err(UVarintError::InvalidTag(tag)),
+        }
+    }
+}
+
+impl<T: VarintEncode + ?Sized> VarintEncode for &T {
+    fn encoded_len(&self) -> usize {
+        (**self).encoded_len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        (**self).encode_into(buf)
+    }
+}
+
+impl<T: VarintEncode + ?Sized> VarintEncode for Box<T> {
+    fn encoded_len(&self) -> usize {
+        (**self).encoded_len()
+    }
+
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        (**self).encode_into(buf)
+    }
+}
+
+impl<T: VarintDecode> VarintDecode for Box<T> {
+    const MIN_ENCODED_LEN: usize = T::MIN_ENCODED_LEN;
+
+    fn decode(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+        T::decode(data).map(|(n, value)| (n, Box::new(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<T: VarintEncode + VarintDecode + PartialEq + std::fmt::Debug>(value: T) {
+        let encoded = value.encode();
+        assert_eq!(encoded.len(), value.encoded_len());
+        assert_eq!(T::decode(&encoded).unwrap(), (encoded.len(), value));
+    }
+
+    #[test]
+    fn test_roundtrip_primitives() {
+        roundtrip(u16::MAX);
+        roundtrip(u128::MAX);
+        roundtrip(usize::MAX);
+        roundtrip(-1i32);
+        roundtrip(i64::MIN);
+        roundtrip(200u8);
+        roundtrip(-3i8);
+        roundtrip(true);
+        roundtrip(1.5f64);
+        roundtrip("héllo".to_string());
+        roundtrip(vec![Some(1u32), None, Some(300)]);
+        roundtrip(Box::new(vec![vec![1i16, -1]]));
+        roundtrip(ZigZag(i128::MIN));
+        roundtrip(ZigZag(-1i8));
+        roundtrip(Fixed(u64::MAX));
+        roundtrip(Fixed(-2i32));
+    }
+
+    #[test]
+    fn test_signed_encodings() {
+        assert_eq!((-1i32).encode(), vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        assert_eq!(ZigZag(-1i32).encode(), vec![0x01]);
+        assert_eq!(Fixed(-1i32).encode(), vec![0xFF; 4]);
+    }
+
+    #[test]
+    fn test_decode_out_of_range() {
+        assert!(matches!(
+            u16::decode(&[0x80, 0x80, 0x04]),
+            Err(UVarintError::Overflow)
+        ));
+        assert!(matches!(
+            ZigZag::<i8>::decode(&[0x80, 0x02]),
+            Err(UVarintError::Overflow)
+        ));
+        assert!(matches!(
+            bool::decode(&[0x02]),
+            Err(UVarintError::InvalidTag(2))
+        ));
+        assert!(matches!(
+            Option::<u8>::decode(&[0x05]),
+            Err(UVarintError::InvalidTag(5))
+        ));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small_writes_nothing() {
+        let value = vec![1u64, 2, 300];
+        let mut buf = [0xAAu8; 4];
+
+        assert!(matches!(
+            value.encode_into(&mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 5 })
+        ));
+        assert_eq!(buf, [0xAA; 4]);
+    }
+
+    #[test]
+    fn test_vec_count_is_bounded() {
+        #[derive(Debug)]
+        struct Unit;
+
+        impl VarintDecode for Unit {
+            fn decode(_: &[u8]) -> Result<(usize, Self), UVarintError> {
+                Ok((0, Unit))
+            }
+        }
+
+        let hostile = crate::encode_u64(u64::MAX - 1);
+        assert!(matches!(
+            Vec::<Unit>::decode(&hostile),
+            Err(UVarintError::SequenceTooLong {
+                max: DEFAULT_MAX_VEC_LEN,
+                ..
+            })
+        ));
+
+        let (n, units) = decode_vec_with_max::<Unit>(&[0x03], 3).unwrap();
+        assert_eq!((n, units.len()), (1, 3));
+
+        // Two-byte elements fail on the count, before decoding any of them
+        let mut data = crate::encode_u64(1000);
+        data.extend([0x01, 0x00, 0x00]);
+        assert!(matches!(
+            Vec::<Fixed<u16>>::decode(&data),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_vec_count_is_not_trusted_for_capacity() {
+        let mut data = crate::encode_u64(DEFAULT_MAX_VEC_LEN as u64);
+        data.push(0x01);
+
+        assert!(matches!(
+            Vec::<u8>::decode(&data),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+//! Serde support built on varints.
+//!
+//! Enabled with the `serde` feature. Two independent pieces live here:
+//!
+//! - Field adapters and the [`Varint`] newtype, which make individual integer
+//!   fields varint-encoded inside any serde format.
+//! - A compact, self-contained binary format ([`to_vec`] / [`from_bytes`])
+//!   that uses varints throughout.
+//!
+//! # Field Adapters
+//!
+//! Binary formats (those whose serializer reports `is_human_readable() ==
+//! false`) receive each integer as a byte string holding its LEB128 encoding;
+//! signed integers are ZigZag-encoded first. Human-readable formats such as
+//! JSON receive the plain integer, so the same struct stays readable there.
+//!
+//! Fields opt in either with a `#[serde(with = "...")]` adapter or by using
+//! the [`Varint`] newtype.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use uvarint::serde::Varint;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "uvarint::serde::u64")]
+//!     id: u64,
+//!     #[serde(with = "uvarint::serde::i32")]
+//!     delta: i32,
+//!     count: Varint<u32>,
+//! }
+//! ```
+//!
+//! # Encoding Format
+//!
+//! The compact format is not self-describing: the reader must know the type,
+//! just as with bincode or postcard.
+//!
+//! ```text
+//! u16 .. u128            LEB128 varint
+//! i16 .. i128            ZigZag, then LEB128 varint
+//! u8, i8                 one raw byte
+//! bool                   one byte, 0 or 1
+//! f32, f64               little-endian IEEE 754
+//! char                   code point as a varint
+//! str, bytes             [len varint][bytes]
+//! Option<T>              0, or 1 followed by T
+//! seq, map               [len varint][elements or key-value pairs]
+//! tuple, struct          fields in order, no prefix
+//! enum variant           [variant index varint][fields]
+//! unit, unit struct      nothing
+//! ```
+//!
+//! Sequence and map lengths come from the input, so the deserializer rejects
+//! lengths above [`DEFAULT_MAX_SEQ_LEN`] (configurable with
+//! [`Deserializer::with_max_seq_len`]) and lengths that the remaining bytes
+//! cannot hold once an element turns out to take at least one byte.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use uvarint::serde::{from_bytes, to_vec};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! enum Event {
+//!     Login { user: String },
+//!     Logout(u64),
+//! }
+//!
+//! let event = Event::Login { user: "ann".into() };
+//! let bytes = to_vec(&event).unwrap();
+//! assert_eq!(bytes, [0x00, 0x03, b'a', b'n', b'n']);
+//! assert_eq!(from_bytes::<Event>(&bytes).unwrap(), event);
+//! ```
+
+mod de;
+mod error;
+mod ser;
+
+use std::{fmt, marker::PhantomData};
+
+use ::serde::{
+    Deserialize, Serialize,
+    de::{DeserializeOwned, SeqAccess, Visitor},
+};
+
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    encode::{encode_u32_into, encode_u64_into, encode_u128_into},
+    error::UVarintError,
+    zigzag::{
+        zigzag_decode_i32, zigzag_decode_i64, zigzag_decode_i128, zigzag_encode_i32,
+        zigzag_encode_i64, zigzag_encode_i128,
+    },
+};
+
+pub use de::{DEFAULT_MAX_SEQ_LEN, Deserializer, from_bytes};
+pub use error::Error;
+pub use ser::{Serializer, to_vec};
+
+/// Longest encoding of any supported integer (a u128).
+const MAX_LEN: usize = 19;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Integer types that can be serialized as a varint.
+///
+/// This trait is sealed; it is implemented for `u32`, `u64`, `u128`, `i32`,
+/// `i64` and `i128`.
+pub trait VarintInt: Copy + Serialize + DeserializeOwned + private::Sealed {
+    #[doc(hidden)]
+    fn encode_varint(self, buf: &mut [u8; MAX_LEN]) -> usize;
+
+    #[doc(hidden)]
+    fn decode_varint(data: &[u8]) -> Result<(usize, Self), UVarintError>;
+}
+
+macro_rules! impl_unsigned {
+    ($ty:ty, $encode:ident, $decode:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl VarintInt for $ty {
+            fn encode_varint(self, buf: &mut [u8; MAX_LEN]) -> usize {
+                $encode(self, buf).expect("19 bytes fit any varint")
+            }
+
+            fn decode_varint(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                $decode(data)
+            }
+        }
+    };
+}
+
+impl_unsigned!(u32, encode_u32_into, decode_u32);
+impl_unsigned!(u64, encode_u64_into, decode_u64);
+impl_unsigned!(u128, encode_u128_into, decode_u128);
+
+macro_rules! impl_signed {
+    ($ty:ty, $unsigned:ty, $zigzag_encode:ident, $zigzag_decode:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl VarintInt for $ty {
+            fn encode_varint(self, buf: &mut [u8; MAX_LEN]) -> usize {
+                $zigzag_encode(self).encode_varint(buf)
+            }
+
+            fn decode_varint(data: &[u8]) -> Result<(usize, Self), UVarintError> {
+                <$unsigned>::decode_varint(data).map(|(n, value)| (n, $zigzag_decode(value)))
+            }
+        }
+    };
+}
+
+impl_signed!(i32, u32, zigzag_encode_i32, zigzag_decode_i32);
+impl_signed!(i64, u64, zigzag_encode_i64, zigzag_decode_i64);
+impl_signed!(i128, u128, zigzag_encode_i128, zigzag_decode_i128);
+
+fn serialize<T: VarintInt, S: ::serde::Serializer>(
+    value: T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        return value.serialize(serializer);
+    }
+
+    let mut buf = [0u8; MAX_LEN];
+    let n = value.encode_varint(&mut buf);
+    serializer.serialize_bytes(&buf[..n])
+}
+
+fn deserialize<'de, T: VarintInt, D: ::serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        return T::deserialize(deserializer);
+    }
+
+    deserializer.deserialize_bytes(VarintVisitor(PhantomData))
+}
+
+struct VarintVisitor<T>(PhantomData<T>);
+
+impl<'de, T: VarintInt> Visitor<'de> for VarintVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a varint-encoded byte string")
+    }
+
+    fn visit_bytes<E: ::serde::de::Error>(self, bytes: &[u8]) -> Result<T, E> {
+        let (n, value) = T::decode_varint(bytes).map_err(E::custom)?;
+        if n != bytes.len() {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+
+        Ok(value)
+    }
+
+    // Some formats hand byte strings to visitors as a sequence of u8
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut buf = [0u8; MAX_LEN];
+        let mut len = 0;
+
+        while let Some(byte) = seq.next_element::<u8>()? {
+            if len == MAX_LEN {
+                return Err(::serde::de::Error::invalid_length(len + 1, &self));
+            }
+            buf[len] = byte;
+            len += 1;
+        }
+
+        self.visit_bytes(&buf[..len])
+    }
+}
+
+macro_rules! adapter {
+    ($name:ident, $ty:ty) => {
+        #[doc = concat!("`#[serde(with)]` adapter for `", stringify!($ty), "` fields.")]
+        pub mod $name {
+            use ::serde::{Deserializer, Serializer};
+
+            #[doc = concat!("Serializes a `", stringify!($ty), "` as a varint.")]
+            pub fn serialize<S: Serializer>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                super::serialize(*value, serializer)
+            }
+
+            #[doc = concat!("Deserializes a varint-encoded `", stringify!($ty), "`.")]
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$ty, D::Error> {
+                super::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+adapter!(u32, u32);
+adapter!(u64, u64);
+adapter!(u128, u128);
+adapter!(i32, i32);
+adapter!(i64, i64);
+adapter!(i128, i128);
+
+/// Integer wrapper that serializes as a varint.
+///
+/// Useful where a `#[serde(with)]` attribute cannot reach, such as elements of
+/// a `Vec` or values of a map.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::serde::Varint;
+///
+/// let ids: Vec<Varint<u64>> = vec![Varint(1), Varint(300)];
+/// assert_eq!(ids[1].0, 300);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Varint<T>(pub T);
+
+impl<T> From<T> for Varint<T> {
+    fn from(value: T) -> Self {
+        Varint(value)
+    }
+}
+
+impl<T: VarintInt> Serialize for Varint<T> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.0, serializer)
+    }
+}
+
+impl<'de, T: VarintInt> Deserialize<'de> for Varint<T> {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Varint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::serde::{Deserialize, Serialize};
+    use serde_test::{
+        Compact, Configure, Token, assert_de_tokens, assert_de_tokens_error, assert_tokens,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "crate::serde::u64")]
+        id: u64,
+        #[serde(with = "crate::serde::i32")]
+        delta: i32,
+        #[serde(with = "crate::serde::i128")]
+        offset: i128,
+        count: Varint<u32>,
+    }
+
+    #[test]
+    fn test_varint_compact() {
+        assert_tokens(&Varint(300u64).compact(), &[Token::Bytes(&[0xAC, 0x02])]);
+        assert_tokens(&Varint(-1i64).compact(), &[Token::Bytes(&[0x01])]);
+        const U128_MAX: &[u8] = &[
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0x03,
+        ];
+        assert_tokens(&Varint(u128::MAX).compact(), &[Token::Bytes(U128_MAX)]);
+        assert_tokens(&Varint(i128::MIN).compact(), &[Token::Bytes(U128_MAX)]);
+    }
+
+    #[test]
+    fn test_varint_readable() {
+        assert_tokens(&Varint(300u64).readable(), &[Token::U64(300)]);
+        assert_tokens(&Varint(-5i32).readable(), &[Token::I32(-5)]);
+    }
+
+    #[test]
+    fn test_struct_adapters() {
+        let record = Record {
+            id: 300,
+            delta: -2,
+            offset: -300,
+            count: Varint(5),
+        };
+
+        assert_tokens(
+            &record.compact(),
+            &[
+                Token::Struct {
+                    name: "Record",
+                    len: 4,
+                },
+                Token::Str("id"),
+                Token::Bytes(&[0xAC, 0x02]),
+                Token::Str("delta"),
+                Token::Bytes(&[0x03]),
+                Token::Str("offset"),
+                Token::Bytes(&[0xD7, 0x04]),
+                Token::Str("count"),
+                Token::Bytes(&[0x05]),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_deserialize_from_seq() {
+        assert_de_tokens(
+            &Varint(300u32).compact(),
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(0xAC),
+                Token::U8(0x02),
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_deserialize_rejects_bad_bytes() {
+        assert_de_tokens_error::<Compact<Varint<u64>>>(
+            &[Token::Bytes(&[0xAC, 0x02, 0x00])],
+            "invalid length 3, expected a varint-encoded byte string",
+        );
+        assert_de_tokens_error::<Compact<Varint<u64>>>(
+            &[Token::Bytes(&[0x80])],
+            "Incomplete varint data",
+        );
+    }
+}
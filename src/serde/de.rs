@@ -0,0 +1,609 @@
+use serde::{
+    Deserialize,
+    de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess},
+    de::{VariantAccess, Visitor},
+};
+
+use super::Error;
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    error::UVarintError,
+    string::{decode_bytes, decode_str},
+    zigzag::{zigzag_decode_i32, zigzag_decode_i64, zigzag_decode_i128},
+};
+
+/// Longest sequence or map the [`Deserializer`] accepts by default. Elements
+/// such as `()` take no bytes, so the length prefix alone cannot be trusted.
+pub const DEFAULT_MAX_SEQ_LEN: usize = 1 << 24;
+
+/// Deserializes a value from `input`, which must hold exactly one value.
+///
+/// Strings and byte slices borrow from `input` where the target type allows.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use uvarint::serde::from_bytes;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Point {
+///     x: u32,
+///     y: i32,
+/// }
+///
+/// assert_eq!(from_bytes::<Point>(&[0xAC, 0x02, 0x01]).unwrap(), Point { x: 300, y: -1 });
+/// ```
+///
+/// # Errors
+///
+/// Returns `Error::TrailingBytes` if bytes remain after the value,
+/// `Error::Varint` if the input is truncated or a varint is malformed, or one
+/// of the other variants if a byte is not valid for the expected type.
+pub fn from_bytes<'de, T: Deserialize<'de>>(input: &'de [u8]) -> Result<T, Error> {
+    let mut deserializer = Deserializer::from_bytes(input);
+    let value = T::deserialize(&mut deserializer)?;
+
+    if !deserializer.input.is_empty() {
+        return Err(Error::TrailingBytes);
+    }
+
+    Ok(value)
+}
+
+/// Compact binary deserializer for data written by
+/// [`Serializer`](super::Serializer).
+///
+/// The format is not self-describing, so `deserialize_any` (and with it
+/// untagged or internally tagged enums and `#[serde(flatten)]`) is not
+/// supported.
+#[derive(Debug)]
+pub struct Deserializer<'de> {
+    input: &'de [u8],
+    max_seq_len: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer reading from `input`, accepting sequences and
+    /// maps of up to [`DEFAULT_MAX_SEQ_LEN`] elements.
+    pub fn from_bytes(input: &'de [u8]) -> Self {
+        Self::with_max_seq_len(input, DEFAULT_MAX_SEQ_LEN)
+    }
+
+    /// Creates a deserializer that rejects sequences and maps longer than
+    /// `max_seq_len` before reading any of their elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use uvarint::{UVarintError, serde::{Deserializer, Error}};
+    ///
+    /// let mut deserializer = Deserializer::with_max_seq_len(&[0x03, 0x01, 0x02, 0x03], 2);
+    /// assert!(matches!(
+    ///     Vec::<u8>::deserialize(&mut deserializer),
+    ///     Err(Error::Varint(UVarintError::SequenceTooLong { count: 3, max: 2 }))
+    /// ));
+    /// ```
+    pub fn with_max_seq_len(input: &'de [u8], max_seq_len: usize) -> Self {
+        Deserializer { input, max_seq_len }
+    }
+
+    /// Returns the input that has not been consumed yet.
+    pub fn remaining(&self) -> &'de [u8] {
+        self.input
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if self.input.len() < len {
+            return Err(UVarintError::Incomplete.into());
+        }
+
+        let (head, tail) = self.input.split_at(len);
+        self.input = tail;
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let (n, value) = decode_u32(self.input)?;
+        self.input = &self.input[n..];
+        Ok(value)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        let (n, value) = decode_u64(self.input)?;
+        self.input = &self.input[n..];
+        Ok(value)
+    }
+
+    fn read_u128(&mut self) -> Result<u128, Error> {
+        let (n, value) = decode_u128(self.input)?;
+        self.input = &self.input[n..];
+        Ok(value)
+    }
+
+    fn read_len(&mut self) -> Result<usize, Error> {
+        let len = self.read_u64()?;
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_seq_len => Ok(len),
+            _ => Err(UVarintError::SequenceTooLong {
+                count: len,
+                max: self.max_seq_len,
+            }
+            .into()),
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8], Error> {
+        let (n, bytes) = decode_bytes(self.input)?;
+        self.input = &self.input[n..];
+        Ok(bytes)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, Error> {
+        let (n, s) = decode_str(self.input)?;
+        self.input = &self.input[n..];
+        Ok(s)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NotSelfDescribing)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.read_u8()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            byte => Err(Error::InvalidBool(byte)),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i8(self.read_u8()? as i8)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = zigzag_decode_i32(self.read_u32()?);
+        visitor.visit_i16(i16::try_from(value).map_err(|_| UVarintError::Overflow)?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(zigzag_decode_i32(self.read_u32()?))
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i64(zigzag_decode_i64(self.read_u64()?))
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i128(zigzag_decode_i128(self.read_u128()?))
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u8(self.read_u8()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = self.read_u32()?;
+        visitor.visit_u16(u16::try_from(value).map_err(|_| UVarintError::Overflow)?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.read_u32()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u64(self.read_u64()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u128(self.read_u128()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let bytes = self.take(4)?.try_into().expect("took 4 bytes");
+        visitor.visit_f32(f32::from_le_bytes(bytes))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let bytes = self.take(8)?.try_into().expect("took 8 bytes");
+        visitor.visit_f64(f64::from_le_bytes(bytes))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = self.read_u32()?;
+        visitor.visit_char(char::from_u32(value).ok_or(Error::InvalidChar(value))?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.read_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.read_u8()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            byte => Err(Error::InvalidOption(byte)),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.read_len()?;
+        visitor.visit_seq(Access::prefixed(self, len))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Access {
+            de: self,
+            len,
+            prefixed: false,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.read_len()?;
+        visitor.visit_map(Access::prefixed(self, len))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_u32(self.read_u32()?)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::NotSelfDescribing)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Yields a known number of elements or entries.
+struct Access<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    len: usize,
+    /// Whether `len` was read from the input rather than fixed by the type.
+    prefixed: bool,
+}
+
+impl<'a, 'de> Access<'a, 'de> {
+    fn prefixed(de: &'a mut Deserializer<'de>, len: usize) -> Self {
+        Access {
+            de,
+            len,
+            prefixed: true,
+        }
+    }
+
+    /// Deserializes one element, then checks a length prefix against what is
+    /// left: once an element has taken bytes, the rest cannot outnumber them.
+    fn next<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Error> {
+        let before = self.de.input.len();
+        let value = seed.deserialize(&mut *self.de)?;
+
+        let after = self.de.input.len();
+        if self.prefixed && after < before && self.len > after {
+            return Err(UVarintError::Incomplete.into());
+        }
+
+        Ok(value)
+    }
+}
+
+impl<'de> SeqAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+
+        self.len -= 1;
+        self.next(seed).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> MapAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+
+        self.len -= 1;
+        self.next(seed).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        self.next(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let index = self.read_u32()?;
+        let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::to_vec;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect { w: u32, h: u32 },
+        Line(i64, i64),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record<'a> {
+        id: u64,
+        offset: i32,
+        big: i128,
+        name: String,
+        tag: &'a str,
+        #[serde(with = "serde_bytes_like")]
+        blob: Vec<u8>,
+        flags: (bool, char, u8, i8, u16, f32),
+        parent: Option<u128>,
+        shapes: Vec<Shape>,
+        attrs: BTreeMap<String, i16>,
+        unit: (),
+    }
+
+    /// Routes a `Vec<u8>` through `serialize_bytes` instead of a sequence.
+    mod serde_bytes_like {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(value)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<u8>, D::Error> {
+            <&[u8]>::deserialize(deserializer).map(<[u8]>::to_vec)
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_record() {
+        let record = Record {
+            id: u64::MAX,
+            offset: -300,
+            big: i128::MIN,
+            name: "compact".to_string(),
+            tag: "borrowed",
+            blob: vec![0, 1, 2, 0xFF],
+            flags: (true, 'λ', 200, -5, 65_535, 1.5),
+            parent: Some(1 << 100),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(2.25),
+                Shape::Rect { w: 3, h: 300 },
+                Shape::Line(-1, i64::MAX),
+            ],
+            attrs: [("a".to_string(), -1), ("b".to_string(), i16::MIN)].into(),
+            unit: (),
+        };
+
+        let encoded = to_vec(&record).unwrap();
+        let decoded: Record = from_bytes(&encoded).unwrap();
+        assert_eq!(decoded, record);
+    }
+
+    #[test]
+    fn test_enum_encoding() {
+        assert_eq!(to_vec(&Shape::Empty).unwrap(), vec![0x00]);
+        assert_eq!(
+            to_vec(&Shape::Rect { w: 3, h: 300 }).unwrap(),
+            vec![0x02, 0x03, 0xAC, 0x02]
+        );
+        assert_eq!(
+            from_bytes::<Shape>(&[0x03, 0x01, 0x02]).unwrap(),
+            Shape::Line(-1, 1)
+        );
+        assert!(from_bytes::<Shape>(&[0x04]).is_err());
+    }
+
+    #[test]
+    fn test_borrowed_str_is_zero_copy() {
+        let encoded = to_vec("hello").unwrap();
+        let decoded: &str = from_bytes(&encoded).unwrap();
+        assert_eq!(decoded.as_ptr(), encoded[1..].as_ptr());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            from_bytes::<u32>(&[0x01, 0x00]),
+            Err(Error::TrailingBytes)
+        ));
+        assert!(matches!(
+            from_bytes::<bool>(&[0x02]),
+            Err(Error::InvalidBool(2))
+        ));
+        assert!(matches!(
+            from_bytes::<Option<u8>>(&[0x07, 0x00]),
+            Err(Error::InvalidOption(7))
+        ));
+        assert!(matches!(
+            from_bytes::<char>(&[0x80, 0xB0, 0x03]),
+            Err(Error::InvalidChar(0xD800))
+        ));
+        assert!(matches!(
+            from_bytes::<u16>(&[0x80, 0x80, 0x04]),
+            Err(Error::Varint(UVarintError::Overflow))
+        ));
+        assert!(matches!(
+            from_bytes::<String>(&[0x02, 0xC3, 0x28]),
+            Err(Error::Varint(UVarintError::InvalidUtf8))
+        ));
+        assert!(matches!(
+            from_bytes::<String>(&[0x05, b'a']),
+            Err(Error::Varint(UVarintError::Incomplete))
+        ));
+        assert!(matches!(
+            from_bytes::<serde::de::IgnoredAny>(&[0x00]),
+            Err(Error::NotSelfDescribing)
+        ));
+    }
+
+    #[test]
+    fn test_hostile_seq_len() {
+        let huge = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        assert!(matches!(
+            from_bytes::<Vec<()>>(&huge),
+            Err(Error::Varint(UVarintError::SequenceTooLong {
+                count: 0xFFFF_FFFF,
+                max: DEFAULT_MAX_SEQ_LEN
+            }))
+        ));
+        assert!(matches!(
+            from_bytes::<BTreeMap<(), ()>>(&huge),
+            Err(Error::Varint(UVarintError::SequenceTooLong { .. }))
+        ));
+
+        // Within the limit, non-empty elements fail after the first one
+        let mut data = crate::encode_u64(1 << 20);
+        data.extend([0x01, 0x02]);
+        assert!(matches!(
+            from_bytes::<Vec<u32>>(&data),
+            Err(Error::Varint(UVarintError::Incomplete))
+        ));
+        assert!(matches!(
+            from_bytes::<BTreeMap<u8, ()>>(&data),
+            Err(Error::Varint(UVarintError::Incomplete))
+        ));
+
+        // Zero-sized elements are fine up to the limit, and tuples are not
+        // checked against the input at all
+        assert_eq!(from_bytes::<Vec<()>>(&[0x03]).unwrap(), vec![(), (), ()]);
+        assert_eq!(from_bytes::<(u8, (), ())>(&[0x07]).unwrap(), (7, (), ()));
+
+        let mut deserializer = Deserializer::with_max_seq_len(&[0x02, 0x01, 0x02], 1);
+        assert!(matches!(
+            Vec::<u8>::deserialize(&mut deserializer),
+            Err(Error::Varint(UVarintError::SequenceTooLong {
+                count: 2,
+                max: 1
+            }))
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::error::UVarintError;
+
+/// Errors produced by the compact serde [`Serializer`](super::Serializer) and
+/// [`Deserializer`](super::Deserializer).
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Varint(#[from] UVarintError),
+
+    #[error("{0}")]
+    Custom(String),
+
+    #[error("Sequence and map lengths must be known up front")]
+    UnknownLength,
+
+    #[error("Format is not self-describing")]
+    NotSelfDescribing,
+
+    #[error("Invalid bool {0}")]
+    InvalidBool(u8),
+
+    #[error("Invalid option tag {0}")]
+    InvalidOption(u8),
+
+    #[error("Invalid char {0:#x}")]
+    InvalidChar(u32),
+
+    #[error("Trailing bytes after value")]
+    TrailingBytes,
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom(msg.to_string())
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    decode::{decode_u32, decode_u64, decode_u128},
+    encode::{encode_u32_into, encode_u64_into, encode_u128_into},
+    error::UVarintError,
+    string::{decode_bytes, encode_bytes_into},
+    zigzag::{
+        zigzag_decode_i32, zigzag_decode_i64, zigzag_decode_i128, zigzag_encode_i32,
+        zigzag_encode_i64, zigzag_encode_i128,
+    },
+};
+
+/// Zero-copy cursor over a byte slice for parsing records field by field.
+///
+/// Each `read_*` method decodes from the current position and advances past
+/// what it consumed, so there are no `(consumed, value)` tuples to thread
+/// through. Byte strings are borrowed from the input rather than copied.
+///
+/// Errors are wrapped in `UVarintError::AtOffset` with the position the
+/// failing read started at, and leave the position unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::SliceReader;
+///
+/// let data = [0xAC, 0x02, 0x02, b'h', b'i', 0x03];
+/// let mut reader = SliceReader::new(&data);
+///
+/// assert_eq!(reader.read_u64().unwrap(), 300);
+/// assert_eq!(reader.read_len_prefixed().unwrap(), b"hi");
+/// assert_eq!(reader.read_i32().unwrap(), -2);
+/// assert!(reader.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a reader positioned at the start of `data`.
+    pub fn new(data: &'a [u8]) -> Self {
+        SliceReader { data, position: 0 }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+
+    /// Returns `true` once every byte has been read.
+    pub fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    /// Reads a `u32` varint.
+    pub fn read_u32(&mut self) -> Result<u32, UVarintError> {
+        self.read(decode_u32)
+    }
+
+    /// Reads a `u64` varint.
+    pub fn read_u64(&mut self) -> Result<u64, UVarintError> {
+        self.read(decode_u64)
+    }
+
+    /// Reads a `u128` varint.
+    pub fn read_u128(&mut self) -> Result<u128, UVarintError> {
+        self.read(decode_u128)
+    }
+
+    /// Reads a ZigZag-encoded `i32`.
+    pub fn read_i32(&mut self) -> Result<i32, UVarintError> {
+        self.read_u32().map(zigzag_decode_i32)
+    }
+
+    /// Reads a ZigZag-encoded `i64`.
+    pub fn read_i64(&mut self) -> Result<i64, UVarintError> {
+        self.read_u64().map(zigzag_decode_i64)
+    }
+
+    /// Reads a ZigZag-encoded `i128`.
+    pub fn read_i128(&mut self) -> Result<i128, UVarintError> {
+        self.read_u128().map(zigzag_decode_i128)
+    }
+
+    /// Reads exactly `n` raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Incomplete` if fewer than `n` bytes remain.
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], UVarintError> {
+        self.read(|data| {
+            let bytes = data.get(..n).ok_or(UVarintError::Incomplete)?;
+            Ok((n, bytes))
+        })
+    }
+
+    /// Reads a varint length prefix and the bytes it covers, as written by
+    /// [`encode_bytes_into`](crate::encode_bytes_into).
+    pub fn read_len_prefixed(&mut self) -> Result<&'a [u8], UVarintError> {
+        self.read(decode_bytes)
+    }
+
+    fn read<T>(
+        &mut self,
+        decode: impl FnOnce(&'a [u8]) -> Result<(usize, T), UVarintError>,
+    ) -> Result<T, UVarintError> {
+        let (n, value) = decode(self.remaining()).map_err(|source| UVarintError::AtOffset {
+            offset: self.position as u64,
+            source: Box::new(source),
+        })?;
+        self.position += n;
+        Ok(value)
+    }
+}
+
+/// Cursor over a caller-provided buffer for serializing a message field by
+/// field, the counterpart of [`SliceReader`].
+///
+/// Each `write_*` method encodes at the current position and advances past
+/// what it wrote, returning the number of bytes written. Nothing allocates,
+/// so a whole message can be built in a fixed buffer.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::SliceWriter;
+///
+/// let mut buf = [0u8; 16];
+/// let mut writer = SliceWriter::new(&mut buf);
+///
+/// writer.write_u64(300).unwrap();
+/// writer.write_len_prefixed(b"hi").unwrap();
+/// writer.write_i32(-2).unwrap();
+/// assert_eq!(writer.written(), &[0xAC, 0x02, 0x02, b'h', b'i', 0x03]);
+/// ```
+#[derive(Debug)]
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Creates a writer that fills `buf` from the start.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, position: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes still free in the buffer.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.position
+    }
+
+    /// Returns the bytes written so far.
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.position]
+    }
+
+    /// Consumes the writer, returning the written part of the buffer.
+    pub fn into_written(self) -> &'a mut [u8] {
+        &mut self.buf[..self.position]
+    }
+
+    /// Writes `value` as a varint.
+    pub fn write_u32(&mut self, value: u32) -> Result<usize, UVarintError> {
+        self.write(|buf| encode_u32_into(value, buf))
+    }
+
+    /// Writes `value` as a varint.
+    pub fn write_u64(&mut self, value: u64) -> Result<usize, UVarintError> {
+        self.write(|buf| encode_u64_into(value, buf))
+    }
+
+    /// Writes `value` as a varint.
+    pub fn write_u128(&mut self, value: u128) -> Result<usize, UVarintError> {
+        self.write(|buf| encode_u128_into(value, buf))
+    }
+
+    /// Writes `value` ZigZag-encoded.
+    pub fn write_i32(&mut self, value: i32) -> Result<usize, UVarintError> {
+        self.write_u32(zigzag_encode_i32(value))
+    }
+
+    /// Writes `value` ZigZag-encoded.
+    pub fn write_i64(&mut self, value: i64) -> Result<usize, UVarintError> {
+        self.write_u64(zigzag_encode_i64(value))
+    }
+
+    /// Writes `value` ZigZag-encoded.
+    pub fn write_i128(&mut self, value: i128) -> Result<usize, UVarintError> {
+        self.write_u128(zigzag_encode_i128(value))
+    }
+
+    /// Writes `bytes` as-is.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::BufferTooSmall` if `bytes` does not fit.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<usize, UVarintError> {
+        self.write(|buf| {
+            let dest = buf
+                .get_mut(..bytes.len())
+                .ok_or(UVarintError::BufferTooSmall {
+                    needed: bytes.len(),
+                })?;
+            dest.copy_from_slice(bytes);
+            Ok(bytes.len())
+        })
+    }
+
+    /// Writes a varint length prefix followed by `bytes`, readable with
+    /// [`SliceReader::read_len_prefixed`].
+    pub fn write_len_prefixed(&mut self, bytes: &[u8]) -> Result<usize, UVarintError> {
+        self.write(|buf| encode_bytes_into(bytes, buf))
+    }
+
+    fn write(
+        &mut self,
+        encode: impl FnOnce(&mut [u8]) -> Result<usize, UVarintError>,
+    ) -> Result<usize, UVarintError> {
+        let n = encode(&mut self.buf[self.position..])?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_bytes_into, encode_u64, encode_u128, zigzag_encode_i64};
+
+    #[test]
+    fn test_read_record() {
+        let mut data = encode_u64(7);
+        data.extend(encode_u128(u128::MAX));
+        data.extend(encode_u64(zigzag_encode_i64(i64::MIN)));
+        let mut buf = [0u8; 8];
+        let n = encode_bytes_into(b"name", &mut buf).unwrap();
+        data.extend(&buf[..n]);
+        data.extend([0xDE, 0xAD]);
+
+        let mut reader = SliceReader::new(&data);
+        assert_eq!(reader.read_u32().unwrap(), 7);
+        assert_eq!(reader.read_u128().unwrap(), u128::MAX);
+        assert_eq!(reader.read_i64().unwrap(), i64::MIN);
+
+        let name = reader.read_len_prefixed().unwrap();
+        assert_eq!(name, b"name");
+        assert_eq!(name.as_ptr(), data[data.len() - 6..].as_ptr());
+
+        assert_eq!(reader.remaining(), &[0xDE, 0xAD]);
+        assert_eq!(reader.read_bytes(2).unwrap(), &[0xDE, 0xAD]);
+        assert_eq!(reader.position(), data.len());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_error_offset_keeps_position() {
+        let data = [0x01, 0x05, b'a', b'b'];
+        let mut reader = SliceReader::new(&data);
+        reader.read_u64().unwrap();
+
+        let err = reader.read_len_prefixed().unwrap_err();
+        assert_eq!(err.to_string(), "At offset 1: Incomplete varint data");
+        assert_eq!(reader.position(), 1);
+
+        assert!(matches!(
+            reader.read_bytes(4),
+            Err(UVarintError::AtOffset { offset: 1, .. })
+        ));
+        assert_eq!(reader.read_bytes(3).unwrap(), &[0x05, b'a', b'b']);
+        assert!(matches!(
+            reader.read_u64(),
+            Err(UVarintError::AtOffset { offset: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_writer_reader_roundtrip() {
+        let mut buf = [0u8; 64];
+        let mut writer = SliceWriter::new(&mut buf);
+        writer.write_u32(u32::MAX).unwrap();
+        writer.write_u128(u128::MAX).unwrap();
+        writer.write_i64(-300).unwrap();
+        writer.write_i128(i128::MIN).unwrap();
+        writer.write_len_prefixed(b"name").unwrap();
+        writer.write_bytes(&[0xDE, 0xAD]).unwrap();
+        let written = writer.into_written();
+
+        let mut reader = SliceReader::new(written);
+        assert_eq!(reader.read_u32().unwrap(), u32::MAX);
+        assert_eq!(reader.read_u128().unwrap(), u128::MAX);
+        assert_eq!(reader.read_i64().unwrap(), -300);
+        assert_eq!(reader.read_i128().unwrap(), i128::MIN);
+        assert_eq!(reader.read_len_prefixed().unwrap(), b"name");
+        assert_eq!(reader.read_bytes(2).unwrap(), &[0xDE, 0xAD]);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_writer_buffer_too_small() {
+        let mut buf = [0u8; 4];
+        let mut writer = SliceWriter::new(&mut buf);
+        assert_eq!(writer.write_u64(300).unwrap(), 2);
+
+        assert!(matches!(
+            writer.write_len_prefixed(b"ab"),
+            Err(UVarintError::BufferTooSmall { needed: 3 })
+        ));
+        assert!(matches!(
+            writer.write_bytes(b"abc"),
+            Err(UVarintError::BufferTooSmall { needed: 3 })
+        ));
+        assert_eq!((writer.position(), writer.remaining()), (2, 2));
+
+        assert_eq!(writer.write_bytes(b"ab").unwrap(), 2);
+        assert_eq!(writer.remaining(), 0);
+    }
+}
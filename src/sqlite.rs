@@ -0,0 +1,225 @@
+//! SQLite's variable-length integers.
+//!
+//! # Encoding Format
+//!
+//! SQLite stores integers big-endian (most significant group first) in 7-bit
+//! groups, with the MSB of each byte acting as a continuation flag. To cap the
+//! encoding at 9 bytes, the 9th byte is not split: it contributes all 8 of its
+//! bits to the value.
+//!
+//! ```text
+//! 300 = 0b10_0101100
+//!     → [1000_0010] [0010_1100]
+//!     → [0x82, 0x2C]
+//! ```
+//!
+//! This matches `sqlite3GetVarint` / `sqlite3PutVarint` in SQLite's `util.c`.
+
+use crate::error::UVarintError;
+
+/// Values above this need the 9-byte form, where the last byte holds 8 bits.
+const MAX_8_BYTE: u64 = 0x00FF_FFFF_FFFF_FFFF;
+
+/// Returns the number of bytes needed to encode `value` as a SQLite varint.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::sqlite::encoded_len;
+///
+/// assert_eq!(encoded_len(127), 1);
+/// assert_eq!(encoded_len(128), 2);
+/// assert_eq!(encoded_len(0x00FF_FFFF_FFFF_FFFF), 8);
+/// assert_eq!(encoded_len(u64::MAX), 9);
+/// ```
+pub fn encoded_len(value: u64) -> usize {
+    if value > MAX_8_BYTE {
+        return 9;
+    }
+
+    let bits = (u64::BITS - value.leading_zeros()).max(1) as usize;
+    bits.div_ceil(7)
+}
+
+/// Encodes a u64 as a SQLite varint into a provided buffer, returning the
+/// number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::sqlite::encode_u64_into;
+///
+/// let mut buf = [0u8; 9];
+/// let n = encode_u64_into(300, &mut buf).unwrap();
+/// assert_eq!(n, 2);
+/// assert_eq!(&buf[..n], &[0x82, 0x2C]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u64_into(value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let len = encoded_len(value);
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    let (mut value, groups) = if len == 9 {
+        buf[8] = value as u8;
+        (value >> 8, 8)
+    } else {
+        (value, len)
+    };
+
+    // Fill from the least significant group backwards, then clear the
+    // continuation bit on the last 7-bit group.
+    for byte in buf[..groups].iter_mut().rev() {
+        *byte = (value & 0x7F) as u8 | 0x80;
+        value >>= 7;
+    }
+
+    if len < 9 {
+        buf[len - 1] &= 0x7F;
+    }
+
+    Ok(len)
+}
+
+/// Decodes a SQLite varint from a byte slice.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::sqlite::decode_u64;
+///
+/// assert_eq!(decode_u64(&[0x82, 0x2C]).unwrap(), (2, 300));
+/// assert_eq!(decode_u64(&[0xFF; 9]).unwrap(), (9, u64::MAX));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the value does.
+pub fn decode_u64(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    let mut value: u64 = 0;
+
+    for (i, &byte) in data.iter().take(9).enumerate() {
+        if i == 8 {
+            return Ok((9, (value << 8) | byte as u64));
+        }
+
+        value = (value << 7) | (byte & 0x7F) as u64;
+
+        if (byte & 0x80) == 0 {
+            return Ok((i + 1, value));
+        }
+    }
+
+    Err(UVarintError::Incomplete)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1024-byte database with `page_size = 512` holding one table:
+    ///
+    /// ```sql
+    /// CREATE TABLE t(id INTEGER PRIMARY KEY, v TEXT);
+    /// ```
+    ///
+    /// Page 2 is the table's leaf page, whose cells start with two varints:
+    /// the payload size and the rowid.
+    const FIXTURE: &[u8] = include_bytes!("../tests/fixtures/rowids.db");
+
+    const ROWIDS: [i64; 10] = [
+        -1,
+        1,
+        127,
+        128,
+        16_383,
+        16_384,
+        2_097_152,
+        72_057_594_037_927_935,
+        72_057_594_037_927_936,
+        i64::MAX,
+    ];
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let values = [
+            0,
+            1,
+            127,
+            128,
+            240,
+            2_287,
+            16_383,
+            16_384,
+            MAX_8_BYTE,
+            MAX_8_BYTE + 1,
+            u64::MAX,
+        ];
+
+        for &value in &values {
+            let mut buf = [0u8; 9];
+            let n = encode_u64_into(value, &mut buf).unwrap();
+            assert_eq!(n, encoded_len(value));
+            assert_eq!(decode_u64(&buf[..n]).unwrap(), (n, value));
+        }
+    }
+
+    #[test]
+    fn test_nine_byte_form() {
+        // 2^56: eight 7-bit groups carry bits 8..63, the 9th byte carries bits 0..7
+        let mut buf = [0u8; 9];
+        let n = encode_u64_into(MAX_8_BYTE + 1, &mut buf).unwrap();
+        assert_eq!(n, 9);
+        assert_eq!(buf, [0x80, 0xC0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0u8; 1];
+        assert!(matches!(
+            encode_u64_into(300, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(matches!(decode_u64(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            decode_u64(&[0x82, 0x80]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_database_fixture() {
+        let page = &FIXTURE[512..1024];
+
+        // Leaf table b-tree page with a big-endian cell count at offset 3
+        assert_eq!(page[0], 0x0D);
+        let cells = u16::from_be_bytes([page[3], page[4]]) as usize;
+        assert_eq!(cells, ROWIDS.len());
+
+        for (i, &rowid) in ROWIDS.iter().enumerate() {
+            let ptr = 8 + i * 2;
+            let offset = u16::from_be_bytes([page[ptr], page[ptr + 1]]) as usize;
+            let cell = &page[offset..];
+
+            let (n, payload_len) = decode_u64(cell).unwrap();
+            assert_eq!((n, payload_len), (1, 4));
+
+            let (m, decoded) = decode_u64(&cell[n..]).unwrap();
+            assert_eq!(decoded as i64, rowid);
+
+            // Re-encoding must reproduce SQLite's bytes exactly
+            let mut buf = [0u8; 9];
+            let written = encode_u64_into(rowid as u64, &mut buf).unwrap();
+            assert_eq!(&buf[..written], &cell[n..n + m]);
+        }
+    }
+}
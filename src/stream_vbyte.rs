@@ -0,0 +1,281 @@
+//! Stream VByte: Group Varint with the tags split into their own stream.
+//!
+//! # Encoding Format
+//!
+//! Like [Group Varint](crate::group_varint), every u32 is stored as 1-4
+//! little-endian bytes and described by a 2-bit `byte_length - 1` code, four
+//! codes per control byte (first value in the low bits). Stream VByte writes
+//! the control bytes and the data bytes to two separate buffers:
+//!
+//! ```text
+//! values:  [1, 300, 70_000, 16_777_216, 5]
+//! control: [0xE4, 0x00]
+//! data:    [0x01, 0x2C, 0x01, 0x70, 0x11, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05]
+//! ```
+//!
+//! Keeping the data contiguous lets a decoder fetch 16 bytes, look up a
+//! shuffle mask by control byte, and place four values with one `pshufb`
+//! (Lemire, Kurz & Rupp, "Stream VByte: Faster Byte-Oriented Integer
+//! Compression", 2017). That SIMD path is enabled with the `simd` feature on
+//! x86_64 CPUs with SSSE3; everything else uses the scalar decoder.
+//!
+//! As with Group Varint, the value count is not stored and must be tracked by
+//! the caller.
+
+use crate::{error::UVarintError, group_varint::byte_len};
+
+/// Number of values described by one control byte.
+const GROUP_SIZE: usize = 4;
+
+/// Returns the number of control bytes needed for `count` values.
+pub const fn control_len(count: usize) -> usize {
+    count.div_ceil(GROUP_SIZE)
+}
+
+/// Encodes a slice of u32 values, returning the control and data streams.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::stream_vbyte::encode;
+///
+/// let (control, data) = encode(&[1, 300, 70_000, 16_777_216, 5]);
+/// assert_eq!(control, vec![0xE4, 0x00]);
+/// assert_eq!(
+///     data,
+///     vec![0x01, 0x2C, 0x01, 0x70, 0x11, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05]
+/// );
+/// ```
+pub fn encode(values: &[u32]) -> (Vec<u8>, Vec<u8>) {
+    let mut control = vec![0u8; control_len(values.len())];
+    let mut data = Vec::with_capacity(values.len() * 2);
+
+    for (i, &value) in values.iter().enumerate() {
+        let n = byte_len(value);
+        control[i / GROUP_SIZE] |= ((n - 1) as u8) << (2 * (i % GROUP_SIZE));
+        data.extend_from_slice(&value.to_le_bytes()[..n]);
+    }
+
+    (control, data)
+}
+
+/// Decodes `out.len()` values from the control and data streams, returning the
+/// number of data bytes consumed.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::stream_vbyte::{decode, encode};
+///
+/// let values = [1, 300, 70_000, 16_777_216, 5];
+/// let (control, data) = encode(&values);
+///
+/// let mut out = [0u32; 5];
+/// assert_eq!(decode(&control, &data, &mut out).unwrap(), data.len());
+/// assert_eq!(out, values);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if either stream ends before `out` is
+/// full.
+pub fn decode(control: &[u8], data: &[u8], out: &mut [u32]) -> Result<usize, UVarintError> {
+    if control.len() < control_len(out.len()) {
+        return Err(UVarintError::Incomplete);
+    }
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("ssse3") {
+        // SAFETY: SSSE3 support was checked above.
+        let (groups, pos) = unsafe { simd::decode_groups(control, data, out) };
+        let start = groups * GROUP_SIZE;
+        return Ok(pos + decode_scalar(&control[groups..], &data[pos..], &mut out[start..])?);
+    }
+
+    decode_scalar(control, data, out)
+}
+
+/// Decodes one value at a time. `control` must hold a code for every slot.
+fn decode_scalar(control: &[u8], data: &[u8], out: &mut [u32]) -> Result<usize, UVarintError> {
+    let mut pos = 0;
+
+    for (i, slot) in out.iter_mut().enumerate() {
+        let code = (control[i / GROUP_SIZE] >> (2 * (i % GROUP_SIZE))) & 0b11;
+        let n = code as usize + 1;
+        let bytes = data.get(pos..pos + n).ok_or(UVarintError::Incomplete)?;
+
+        let mut le = [0u8; 4];
+        le[..n].copy_from_slice(bytes);
+        *slot = u32::from_le_bytes(le);
+        pos += n;
+    }
+
+    Ok(pos)
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd {
+    use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_shuffle_epi8, _mm_storeu_si128};
+
+    use super::GROUP_SIZE;
+
+    /// Per control byte: a `pshufb` mask that spreads the packed data bytes
+    /// into four u32 lanes (0x80 zeroes a lane byte), and the bytes consumed.
+    static TABLES: ([[u8; 16]; 256], [u8; 256]) = build_tables();
+
+    const fn build_tables() -> ([[u8; 16]; 256], [u8; 256]) {
+        let mut masks = [[0x80u8; 16]; 256];
+        let mut lens = [0u8; 256];
+
+        let mut control = 0;
+        while control < 256 {
+            let mut src = 0u8;
+            let mut lane = 0;
+            while lane < GROUP_SIZE {
+                let n = ((control >> (2 * lane)) & 0b11) + 1;
+                let mut b = 0;
+                while b < n {
+                    masks[control][lane * 4 + b] = src;
+                    src += 1;
+                    b += 1;
+                }
+                lane += 1;
+            }
+            lens[control] = src;
+            control += 1;
+        }
+
+        (masks, lens)
+    }
+
+    /// Decodes whole groups while a full 16-byte load stays inside `data`.
+    /// Returns the number of groups decoded and the data bytes consumed; the
+    /// caller finishes the rest with the scalar decoder.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support SSSE3, and `control` must hold a code for every
+    /// slot of `out`.
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn decode_groups(
+        control: &[u8],
+        data: &[u8],
+        out: &mut [u32],
+    ) -> (usize, usize) {
+        let (masks, lens) = &TABLES;
+        let full_groups = out.len() / GROUP_SIZE;
+        let mut pos = 0;
+        let mut group = 0;
+
+        while group < full_groups && pos + 16 <= data.len() {
+            let c = control[group] as usize;
+
+            // SAFETY: `pos + 16 <= data.len()` and `out` holds four u32s at
+            // `group * 4`; both accesses are unaligned loads/stores.
+            unsafe {
+                let bytes = _mm_loadu_si128(data.as_ptr().add(pos) as *const __m128i);
+                let mask = _mm_loadu_si128(masks[c].as_ptr() as *const __m128i);
+                let lanes = _mm_shuffle_epi8(bytes, mask);
+                _mm_storeu_si128(
+                    out.as_mut_ptr().add(group * GROUP_SIZE) as *mut __m128i,
+                    lanes,
+                );
+            }
+
+            pos += lens[c] as usize;
+            group += 1;
+        }
+
+        (group, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(count: usize) -> Vec<u32> {
+        (0..count as u32)
+            .map(|i| i.wrapping_mul(2_654_435_761) >> (i % 32))
+            .collect()
+    }
+
+    #[test]
+    fn test_roundtrip_all_tail_lengths() {
+        for count in 0..=40 {
+            let values = sample(count);
+            let (control, data) = encode(&values);
+            assert_eq!(control.len(), control_len(count));
+
+            let mut out = vec![0u32; count];
+            assert_eq!(decode(&control, &data, &mut out).unwrap(), data.len());
+            assert_eq!(out, values);
+        }
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let values = [
+            0,
+            u32::MAX,
+            255,
+            256,
+            65_535,
+            65_536,
+            16_777_215,
+            16_777_216,
+        ];
+        let (control, data) = encode(&values);
+        assert_eq!(data.len(), 1 + 4 + 1 + 2 + 2 + 3 + 3 + 4);
+
+        let mut out = [0u32; 8];
+        decode(&control, &data, &mut out).unwrap();
+        assert_eq!(out, values);
+    }
+
+    #[test]
+    fn test_matches_group_varint_layout() {
+        let values = sample(13);
+        let (control, data) = encode(&values);
+        let grouped = crate::group_varint::encode_u32_group(&values);
+
+        // Group Varint interleaves the same control bytes with the same data
+        let mut interleaved = Vec::new();
+        let mut pos = 0;
+        for (c, chunk) in control.iter().zip(values.chunks(GROUP_SIZE)) {
+            let n: usize = chunk.iter().map(|&v| byte_len(v)).sum();
+            interleaved.push(*c);
+            interleaved.extend_from_slice(&data[pos..pos + n]);
+            pos += n;
+        }
+        assert_eq!(interleaved, grouped);
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        let values = sample(9);
+        let (control, data) = encode(&values);
+        let mut out = vec![0u32; 9];
+
+        assert!(matches!(
+            decode(&control[..2], &data, &mut out),
+            Err(UVarintError::Incomplete)
+        ));
+        assert!(matches!(
+            decode(&control, &data[..data.len() - 1], &mut out),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_large_input_uses_fast_path() {
+        // Long enough that most groups go through the 16-byte load when the
+        // `simd` feature is enabled, with the tail finished by the scalar loop
+        let values = sample(1_027);
+        let (control, data) = encode(&values);
+
+        let mut out = vec![0u32; values.len()];
+        assert_eq!(decode(&control, &data, &mut out).unwrap(), data.len());
+        assert_eq!(out, values);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{decode::decode_u64, error::UVarintError, frame::encode_frame_into};
+
+/// Writes a varint length prefix followed by `bytes` into a provided buffer,
+/// returning the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_bytes_into;
+///
+/// let mut buf = [0u8; 8];
+/// let n = encode_bytes_into(&[0xDE, 0xAD], &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0x02, 0xDE, 0xAD]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the prefix and bytes do not fit.
+/// Nothing is written in that case.
+pub fn encode_bytes_into(bytes: &[u8], buf: &mut [u8]) -> Result<usize, UVarintError> {
+    encode_frame_into(bytes, buf)
+}
+
+/// Reads a varint-length-prefixed byte string, returning the bytes consumed
+/// and a slice borrowed from `data`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_bytes;
+///
+/// assert_eq!(decode_bytes(&[0x02, 0xDE, 0xAD, 0x00]).unwrap(), (3, &[0xDE, 0xAD][..]));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends before the bytes do,
+/// or `UVarintError::Overflow` if the length does not fit in a `usize`.
+pub fn decode_bytes(data: &[u8]) -> Result<(usize, &[u8]), UVarintError> {
+    let (n, len) = decode_u64(data)?;
+    let len = usize::try_from(len).map_err(|_| UVarintError::Overflow)?;
+
+    let end = n.checked_add(len).ok_or(UVarintError::Incomplete)?;
+    let bytes = data.get(n..end).ok_or(UVarintError::Incomplete)?;
+    Ok((end, bytes))
+}
+
+/// Writes a varint length prefix followed by the UTF-8 bytes of `s` into a
+/// provided buffer, returning the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::encode_str_into;
+///
+/// let mut buf = [0u8; 8];
+/// let n = encode_str_into("héllo", &mut buf).unwrap();
+/// assert_eq!(&buf[..n], b"\x06h\xC3\xA9llo");
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the prefix and string do not fit.
+/// Nothing is written in that case.
+pub fn encode_str_into(s: &str, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    encode_bytes_into(s.as_bytes(), buf)
+}
+
+/// Reads a varint-length-prefixed UTF-8 string, returning the bytes consumed
+/// and a `&str` borrowed from `data`.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_str;
+///
+/// assert_eq!(decode_str(b"\x02hi!").unwrap(), (3, "hi"));
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::InvalidUtf8` if the bytes are not valid UTF-8, plus
+/// the errors of [`decode_bytes`].
+pub fn decode_str(data: &[u8]) -> Result<(usize, &str), UVarintError> {
+    let (n, bytes) = decode_bytes(data)?;
+    let s = std::str::from_utf8(bytes).map_err(|_| UVarintError::InvalidUtf8)?;
+    Ok((n, s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str_roundtrip() {
+        let long = "ü".repeat(100);
+
+        for s in ["", "a", "héllo wörld", long.as_str()] {
+            let mut buf = vec![0u8; s.len() + 2];
+            let n = encode_str_into(s, &mut buf).unwrap();
+
+            let (consumed, decoded) = decode_str(&buf[..n]).unwrap();
+            assert_eq!(consumed, n);
+            assert_eq!(decoded, s);
+            assert_eq!(decoded.as_ptr(), buf[n - s.len()..].as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_decode_str_invalid_utf8() {
+        assert!(matches!(
+            decode_str(&[0x02, 0xC3, 0x28]),
+            Err(UVarintError::InvalidUtf8)
+        ));
+
+        // The same bytes are fine as a byte string
+        assert_eq!(
+            decode_bytes(&[0x02, 0xC3, 0x28]).unwrap(),
+            (3, &[0xC3, 0x28][..])
+        );
+    }
+
+    #[test]
+    fn test_decode_bytes_incomplete() {
+        assert!(matches!(decode_bytes(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            decode_bytes(&[0x03, 0x01, 0x02]),
+            Err(UVarintError::Incomplete)
+        ));
+
+        // A huge length must not wrap around when added to the prefix length
+        let huge = crate::encode_u64(u64::MAX);
+        assert!(decode_bytes(&huge).is_err());
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0xAAu8; 3];
+        assert!(matches!(
+            encode_str_into("abc", &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 4 })
+        ));
+        assert_eq!(buf, [0xAA; 3]);
+    }
+}
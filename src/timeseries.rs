@@ -0,0 +1,263 @@
+//! Delta-of-delta timestamp compression.
+//!
+//! # Encoding Format
+//!
+//! Timestamps in a metrics column usually arrive at a near-constant interval,
+//! so the *change* in the interval is almost always 0 or close to it. Following
+//! Facebook's Gorilla paper, each timestamp is stored as:
+//!
+//! ```text
+//! t[0]                                   first value
+//! t[1] - t[0]                            first delta
+//! (t[i] - t[i-1]) - (t[i-1] - t[i-2])    delta-of-delta, for i >= 2
+//! ```
+//!
+//! Every term is ZigZag-encoded, so negative jumps stay small, and written as
+//! a LEB128 varint. A column sampled every 10 seconds costs one byte per
+//! timestamp after the first two.
+//!
+//! All arithmetic wraps, which keeps the round trip exact for any input, even
+//! jumps that overflow an i64.
+//!
+//! # Examples
+//!
+//! ```
+//! use uvarint::timeseries;
+//!
+//! let timestamps = [1_700_000_000, 1_700_000_010, 1_700_000_020, 1_700_000_031];
+//! let encoded = timeseries::encode_i64(&timestamps);
+//!
+//! // 5 bytes for the first value, then 1 byte each: 20, 0, 2
+//! assert_eq!(encoded.len(), 8);
+//! assert_eq!(timeseries::decode_i64(&encoded).unwrap(), timestamps);
+//! ```
+
+use crate::{
+    decode,
+    encode::{encode_u64_into, encoded_len_u64},
+    error::UVarintError,
+    zigzag::{zigzag_decode_i64, zigzag_encode_i64},
+};
+
+/// Encodes a column of signed timestamps as ZigZag delta-of-delta varints.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::timeseries::encode_i64;
+///
+/// // 100, +10, +0, -10 (interval drops from 10 to 0)
+/// assert_eq!(encode_i64(&[100, 110, 120, 120]), vec![0xC8, 0x01, 0x14, 0x00, 0x13]);
+/// ```
+pub fn encode_i64(timestamps: &[i64]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(timestamps.len() + 8);
+    let mut buf = [0u8; 10];
+    let mut prev = 0i64;
+    let mut prev_delta = 0i64;
+
+    for (i, &timestamp) in timestamps.iter().enumerate() {
+        let delta = timestamp.wrapping_sub(prev);
+        let term = match i {
+            0 => timestamp,
+            1 => delta,
+            _ => delta.wrapping_sub(prev_delta),
+        };
+
+        let n = encode_u64_into(zigzag_encode_i64(term), &mut buf)
+            .expect("10 bytes fit any u64 varint");
+        result.extend_from_slice(&buf[..n]);
+
+        prev_delta = delta;
+        prev = timestamp;
+    }
+
+    result
+}
+
+/// Decodes a column produced by [`encode_i64`].
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::timeseries::decode_i64;
+///
+/// assert_eq!(
+///     decode_i64(&[0xC8, 0x01, 0x14, 0x00, 0x13]).unwrap(),
+///     vec![100, 110, 120, 120]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends mid-varint, or
+/// `UVarintError::Overflow` if a varint does not fit in a u64.
+pub fn decode_i64(data: &[u8]) -> Result<Vec<i64>, UVarintError> {
+    let mut result = Vec::new();
+    let mut data = data;
+    let mut prev = 0i64;
+    let mut prev_delta = 0i64;
+
+    while !data.is_empty() {
+        let (n, raw) = decode::decode_u64(data)?;
+        data = &data[n..];
+
+        let term = zigzag_decode_i64(raw);
+        let timestamp = match result.len() {
+            0 => term,
+            1 => prev.wrapping_add(term),
+            _ => prev.wrapping_add(prev_delta).wrapping_add(term),
+        };
+
+        prev_delta = timestamp.wrapping_sub(prev);
+        prev = timestamp;
+        result.push(timestamp);
+    }
+
+    Ok(result)
+}
+
+/// Encodes a column of unsigned timestamps as ZigZag delta-of-delta varints.
+///
+/// Values are reinterpreted as i64 bit patterns, so the full u64 range round
+/// trips.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::timeseries::{decode_u64, encode_u64};
+///
+/// let timestamps = [0, 60, 120, 180, 240, 239];
+/// assert_eq!(decode_u64(&encode_u64(&timestamps)).unwrap(), timestamps);
+/// ```
+pub fn encode_u64(timestamps: &[u64]) -> Vec<u8> {
+    let signed: Vec<i64> = timestamps.iter().map(|&t| t as i64).collect();
+    encode_i64(&signed)
+}
+
+/// Decodes a column produced by [`encode_u64`].
+///
+/// # Errors
+///
+/// Returns `UVarintError::Incomplete` if the slice ends mid-varint, or
+/// `UVarintError::Overflow` if a varint does not fit in a u64.
+pub fn decode_u64(data: &[u8]) -> Result<Vec<u64>, UVarintError> {
+    Ok(decode_i64(data)?.into_iter().map(|t| t as u64).collect())
+}
+
+/// Returns the number of bytes [`encode_i64`] would produce for `timestamps`.
+pub fn encoded_len_i64(timestamps: &[i64]) -> usize {
+    let mut len = 0;
+    let mut prev = 0i64;
+    let mut prev_delta = 0i64;
+
+    for (i, &timestamp) in timestamps.iter().enumerate() {
+        let delta = timestamp.wrapping_sub(prev);
+        let term = match i {
+            0 => timestamp,
+            1 => delta,
+            _ => delta.wrapping_sub(prev_delta),
+        };
+
+        len += encoded_len_u64(zigzag_encode_i64(term));
+        prev_delta = delta;
+        prev = timestamp;
+    }
+
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic generator so the property tests are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_regular_interval_is_one_byte() {
+        let timestamps: Vec<i64> = (0..1_000).map(|i| 1_700_000_000_000 + i * 15_000).collect();
+        let encoded = encode_i64(&timestamps);
+
+        let header = encoded_len_u64(zigzag_encode_i64(timestamps[0]))
+            + encoded_len_u64(zigzag_encode_i64(15_000));
+        assert_eq!(encoded.len(), header + 998);
+        assert_eq!(decode_i64(&encoded).unwrap(), timestamps);
+    }
+
+    #[test]
+    fn test_roundtrip_irregular_intervals() {
+        let mut rng = Lcg(1);
+
+        for len in 0..64 {
+            let mut t = (rng.next() >> 20) as i64;
+            let timestamps: Vec<i64> = (0..len)
+                .map(|_| {
+                    // Mostly ~1s apart, with jitter and occasional long gaps
+                    let jitter = (rng.next() % 200) as i64 - 100;
+                    let gap = if rng.next().is_multiple_of(10) {
+                        3_600_000
+                    } else {
+                        0
+                    };
+                    t += 1_000 + jitter + gap;
+                    t
+                })
+                .collect();
+
+            let encoded = encode_i64(&timestamps);
+            assert_eq!(encoded.len(), encoded_len_i64(&timestamps));
+            assert_eq!(decode_i64(&encoded).unwrap(), timestamps);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_negative_jumps() {
+        let mut rng = Lcg(2);
+
+        for len in 0..64 {
+            let timestamps: Vec<i64> = (0..len)
+                .map(|_| (rng.next() % 2_000_000) as i64 - 1_000_000)
+                .collect();
+
+            assert_eq!(decode_i64(&encode_i64(&timestamps)).unwrap(), timestamps);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_extremes() {
+        let timestamps = [i64::MIN, i64::MAX, 0, i64::MIN, -1, i64::MAX, i64::MAX];
+        assert_eq!(decode_i64(&encode_i64(&timestamps)).unwrap(), timestamps);
+
+        let unsigned = [u64::MAX, 0, u64::MAX, 1 << 63, 0];
+        assert_eq!(decode_u64(&encode_u64(&unsigned)).unwrap(), unsigned);
+    }
+
+    #[test]
+    fn test_roundtrip_random_u64() {
+        let mut rng = Lcg(3);
+
+        for len in 0..32 {
+            let timestamps: Vec<u64> = (0..len).map(|_| rng.next()).collect();
+            assert_eq!(decode_u64(&encode_u64(&timestamps)).unwrap(), timestamps);
+        }
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(decode_i64(&[]).unwrap().is_empty());
+        assert!(matches!(
+            decode_i64(&[0x02, 0x80]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+}
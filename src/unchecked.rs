@@ -0,0 +1,303 @@
+use std::iter::FusedIterator;
+
+use crate::{decode::decode_u64, error::UVarintError};
+
+/// Longest u32 and u64 varints.
+const MAX_LEN_U32: usize = 5;
+const MAX_LEN_U64: usize = 10;
+
+/// Decodes a u32 varint starting at `ptr` without any bounds or overflow
+/// checks, returning the bytes consumed and the value.
+///
+/// Meant for hot paths over data that is already known to be well-formed,
+/// e.g. written by this crate or checked once with [`validate_u64_stream`].
+/// Payload bits beyond 32 are discarded.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads up to and including the first byte with a
+/// clear MSB, and that byte must be among the first 5.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_u32_unchecked;
+///
+/// let data = [0xAC, 0x02];
+/// // SAFETY: the second byte ends the varint.
+/// assert_eq!(unsafe { decode_u32_unchecked(data.as_ptr()) }, (2, 300));
+/// ```
+pub unsafe fn decode_u32_unchecked(ptr: *const u8) -> (usize, u32) {
+    let mut value: u32 = 0;
+
+    for i in 0..MAX_LEN_U32 {
+        // SAFETY: the caller guarantees every byte up to the terminating one
+        // is readable, and we stop at it.
+        let byte = unsafe { *ptr.add(i) };
+        value |= ((byte & 0x7F) as u32) << (i * 7);
+
+        if (byte & 0x80) == 0 {
+            return (i + 1, value);
+        }
+    }
+
+    (MAX_LEN_U32, value)
+}
+
+/// Decodes a u64 varint starting at `ptr` without any bounds or overflow
+/// checks, returning the bytes consumed and the value.
+///
+/// Meant for hot paths over data that is already known to be well-formed,
+/// e.g. written by this crate or checked once with [`validate_u64_stream`].
+/// Payload bits beyond 64 are discarded.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads up to and including the first byte with a
+/// clear MSB, and that byte must be among the first 10.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::decode_u64_unchecked;
+///
+/// let data = [0xAC, 0x02, 0xFF];
+/// // SAFETY: the second byte ends the varint.
+/// assert_eq!(unsafe { decode_u64_unchecked(data.as_ptr()) }, (2, 300));
+/// ```
+pub unsafe fn decode_u64_unchecked(ptr: *const u8) -> (usize, u64) {
+    let mut value: u64 = 0;
+
+    for i in 0..MAX_LEN_U64 {
+        // SAFETY: the caller guarantees every byte up to the terminating one
+        // is readable, and we stop at it.
+        let byte = unsafe { *ptr.add(i) };
+        value |= ((byte & 0x7F) as u64) << (i * 7);
+
+        if (byte & 0x80) == 0 {
+            return (i + 1, value);
+        }
+    }
+
+    (MAX_LEN_U64, value)
+}
+
+/// Checks that `data` is a sequence of complete u64 varints, none of which
+/// overflows, and returns it as a [`ValidatedU64Stream`] that can be decoded
+/// without further checks.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::validate_u64_stream;
+///
+/// let stream = validate_u64_stream(&[0xAC, 0x02, 0xFF, 0x01, 0x05]).unwrap();
+/// assert_eq!(stream.len(), 3);
+/// assert_eq!(stream.iter().collect::<Vec<_>>(), vec![300, 255, 5]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::AtOffset` with the start of the first bad varint,
+/// wrapping `UVarintError::Incomplete` if the data ends inside it or
+/// `UVarintError::Overflow` if it does not fit in a u64.
+pub fn validate_u64_stream(data: &[u8]) -> Result<ValidatedU64Stream<'_>, UVarintError> {
+    let mut count = 0;
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let len = validate_one(&data[pos..]).map_err(|source| UVarintError::AtOffset {
+            offset: pos as u64,
+            source: Box::new(source),
+        })?;
+        pos += len;
+        count += 1;
+    }
+
+    Ok(ValidatedU64Stream { data, count })
+}
+
+/// Returns the length of the u64 varint at the start of `data`, accepting
+/// exactly what [`decode_u64`] accepts.
+fn validate_one(data: &[u8]) -> Result<usize, UVarintError> {
+    match decode_u64(data) {
+        Ok((n, _)) => Ok(n),
+        // Ten bytes all carrying a continuation bit can never terminate
+        Err(UVarintError::Incomplete) if data.len() >= MAX_LEN_U64 => Err(UVarintError::Overflow),
+        Err(e) => Err(e),
+    }
+}
+
+/// A byte slice proven by [`validate_u64_stream`] to hold only complete,
+/// in-range u64 varints.
+///
+/// Iterating decodes with [`decode_u64_unchecked`], skipping the per-byte
+/// bounds and overflow checks of [`iter_u64`](crate::iter_u64).
+#[derive(Debug, Clone, Copy)]
+pub struct ValidatedU64Stream<'a> {
+    data: &'a [u8],
+    count: usize,
+}
+
+impl<'a> ValidatedU64Stream<'a> {
+    /// Returns the number of varints in the stream.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the stream holds no varints.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the validated bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns an iterator that decodes the values without re-checking them.
+    pub fn iter(&self) -> ValidatedU64Iter<'a> {
+        ValidatedU64Iter {
+            data: self.data,
+            remaining: self.count,
+        }
+    }
+}
+
+impl<'a> IntoIterator for ValidatedU64Stream<'a> {
+    type Item = u64;
+    type IntoIter = ValidatedU64Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`ValidatedU64Stream`].
+#[derive(Debug, Clone)]
+pub struct ValidatedU64Iter<'a> {
+    data: &'a [u8],
+    remaining: usize,
+}
+
+impl Iterator for ValidatedU64Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // SAFETY: `data` is a suffix of a validated stream that starts on a
+        // varint boundary, so a terminating byte follows within 10 bytes.
+        let (n, value) = unsafe { decode_u64_unchecked(self.data.as_ptr()) };
+        self.data = &self.data[n..];
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for ValidatedU64Iter<'_> {}
+
+impl FusedIterator for ValidatedU64Iter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_u32, encode_u32, encode_u64, iter_u64};
+
+    const VALUES: [u64; 9] = [
+        0,
+        1,
+        127,
+        128,
+        300,
+        16_384,
+        u32::MAX as u64,
+        u32::MAX as u64 + 1,
+        u64::MAX,
+    ];
+
+    #[test]
+    fn test_unchecked_matches_checked() {
+        for value in VALUES {
+            let encoded = encode_u64(value);
+            // SAFETY: `encoded` is a complete varint.
+            let unchecked = unsafe { decode_u64_unchecked(encoded.as_ptr()) };
+            assert_eq!(unchecked, decode_u64(&encoded).unwrap());
+
+            if let Ok(value) = u32::try_from(value) {
+                let encoded = encode_u32(value);
+                // SAFETY: `encoded` is a complete varint.
+                let unchecked = unsafe { decode_u32_unchecked(encoded.as_ptr()) };
+                assert_eq!(unchecked, decode_u32(&encoded).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_validated_stream_matches_iter() {
+        let data: Vec<u8> = VALUES.iter().flat_map(|&v| encode_u64(v)).collect();
+        let stream = validate_u64_stream(&data).unwrap();
+
+        assert_eq!(stream.len(), VALUES.len());
+        assert_eq!(stream.iter().len(), VALUES.len());
+        let expected: Vec<u64> = iter_u64(&data).map(Result::unwrap).collect();
+        assert_eq!(stream.into_iter().collect::<Vec<_>>(), expected);
+
+        assert!(validate_u64_stream(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_input() {
+        let err = validate_u64_stream(&[0x01, 0xAC, 0x02, 0x80]).unwrap_err();
+        assert_eq!(err.to_string(), "At offset 3: Incomplete varint data");
+
+        // Ten continuation bytes
+        let mut data = vec![0x05];
+        data.extend([0xFF; 10]);
+        data.push(0x01);
+        assert!(matches!(
+            validate_u64_stream(&data),
+            Err(UVarintError::AtOffset { offset: 1, source }) if matches!(*source, UVarintError::Overflow)
+        ));
+
+        // Tenth byte carries bits beyond u64
+        let mut data = vec![0xFF; 9];
+        data.push(0x02);
+        assert!(matches!(
+            validate_u64_stream(&data),
+            Err(UVarintError::AtOffset { offset: 0, source }) if matches!(*source, UVarintError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_validate_accepts_what_iter_accepts() {
+        let mut max = vec![0xFF; 9];
+        max.push(0x01);
+        let mut overflow = vec![0xFF; 9];
+        overflow.push(0x7F);
+        // Overlong encoding of zero
+        let overlong = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+        let streams: [&[u8]; 6] = [
+            &max,
+            &overflow,
+            &overlong,
+            &[0x80; 10],
+            &[0x81, 0x00],
+            &[0xAC],
+        ];
+
+        for data in streams {
+            let iterated: Result<Vec<u64>, _> = iter_u64(data).collect();
+            match validate_u64_stream(data) {
+                Ok(stream) => assert_eq!(stream.iter().collect::<Vec<_>>(), iterated.unwrap()),
+                Err(_) => assert!(iterated.is_err(), "{data:02X?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,502 @@
+//! Big-endian variable-length quantities (VLQ).
+//!
+//! # Encoding Format
+//!
+//! VLQ is LEB128 with the byte order reversed: 7-bit groups are written most
+//! significant first, and the MSB of every byte except the last is set.
+//!
+//! ```text
+//! 300 = 0b10_0101100
+//!     → [1000_0010] [0010_1100]
+//!     → [0x82, 0x2C]
+//! ```
+//!
+//! Standard MIDI files use VLQ for delta times and cap them at 4 bytes
+//! (`0x0FFF_FFFF`). The free functions in this module accept any value that
+//! fits the integer type; use [`Vlq::MIDI`] (or [`Vlq::new`] for other limits)
+//! to reject anything longer.
+
+use std::io::{Read, Write};
+
+use crate::error::UVarintError;
+
+/// Maximum encoded length of a u32.
+const MAX_LEN_U32: usize = 5;
+
+/// Maximum encoded length of a u64.
+const MAX_LEN_U64: usize = 10;
+
+/// A VLQ codec that enforces a maximum encoded length.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::{UVarintError, vlq::Vlq};
+///
+/// let mut buf = [0u8; 5];
+/// assert_eq!(Vlq::MIDI.encode_u32_into(0x0FFF_FFFF, &mut buf).unwrap(), 4);
+/// assert!(matches!(
+///     Vlq::MIDI.encode_u32_into(0x1000_0000, &mut buf),
+///     Err(UVarintError::Overflow)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vlq {
+    max_len: usize,
+}
+
+impl Vlq {
+    /// Limit used by Standard MIDI files: 4 bytes, or values up to `0x0FFF_FFFF`.
+    pub const MIDI: Vlq = Vlq { max_len: 4 };
+
+    /// Creates a codec that rejects encodings longer than `max_len` bytes.
+    pub const fn new(max_len: usize) -> Self {
+        Vlq { max_len }
+    }
+
+    /// Returns the maximum encoded length this codec accepts.
+    pub const fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Encodes a u32 into a provided buffer, returning the number of bytes
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Overflow` if the value needs more than
+    /// `max_len` bytes, or `UVarintError::BufferTooSmall` if the buffer is
+    /// too small.
+    pub fn encode_u32_into(&self, value: u32, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        encode_into(value as u64, self.max_len.min(MAX_LEN_U32), buf)
+    }
+
+    /// Encodes a u64 into a provided buffer, returning the number of bytes
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Overflow` if the value needs more than
+    /// `max_len` bytes, or `UVarintError::BufferTooSmall` if the buffer is
+    /// too small.
+    pub fn encode_u64_into(&self, value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+        encode_into(value, self.max_len.min(MAX_LEN_U64), buf)
+    }
+
+    /// Decodes a u32 from a byte slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use uvarint::{UVarintError, vlq::Vlq};
+    ///
+    /// assert_eq!(Vlq::MIDI.decode_u32(&[0xFF, 0xFF, 0xFF, 0x7F]).unwrap(), (4, 0x0FFF_FFFF));
+    /// assert!(matches!(
+    ///     Vlq::MIDI.decode_u32(&[0x81, 0x80, 0x80, 0x80, 0x00]),
+    ///     Err(UVarintError::Overflow)
+    /// ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Incomplete` if the slice ends before the value
+    /// does, or `UVarintError::Overflow` if the value is longer than `max_len`
+    /// bytes or does not fit in a u32.
+    pub fn decode_u32(&self, data: &[u8]) -> Result<(usize, u32), UVarintError> {
+        let (n, value) = decode(data, self.max_len.min(MAX_LEN_U32), u32::BITS)?;
+        Ok((n, value as u32))
+    }
+
+    /// Decodes a u64 from a byte slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UVarintError::Incomplete` if the slice ends before the value
+    /// does, or `UVarintError::Overflow` if the value is longer than `max_len`
+    /// bytes or does not fit in a u64.
+    pub fn decode_u64(&self, data: &[u8]) -> Result<(usize, u64), UVarintError> {
+        decode(data, self.max_len.min(MAX_LEN_U64), u64::BITS)
+    }
+
+    /// Read a u32 from any `Read` implementation
+    pub fn read_u32<R: Read>(&self, reader: &mut R) -> Result<u32, UVarintError> {
+        let max_len = self.max_len.min(MAX_LEN_U32);
+        let (buf, len) = read_raw(reader, max_len)?;
+        let (_, value) = decode(&buf[..len], max_len, u32::BITS)?;
+        Ok(value as u32)
+    }
+
+    /// Read a u64 from any `Read` implementation
+    pub fn read_u64<R: Read>(&self, reader: &mut R) -> Result<u64, UVarintError> {
+        let max_len = self.max_len.min(MAX_LEN_U64);
+        let (buf, len) = read_raw(reader, max_len)?;
+        decode(&buf[..len], max_len, u64::BITS).map(|(_, value)| value)
+    }
+
+    /// Write a u32 to any `Write` implementation
+    pub fn write_u32<W: Write>(&self, writer: &mut W, value: u32) -> Result<usize, UVarintError> {
+        let mut buf = [0u8; MAX_LEN_U32];
+        let n = self.encode_u32_into(value, &mut buf)?;
+        write_all(writer, &buf[..n])
+    }
+
+    /// Write a u64 to any `Write` implementation
+    pub fn write_u64<W: Write>(&self, writer: &mut W, value: u64) -> Result<usize, UVarintError> {
+        let mut buf = [0u8; MAX_LEN_U64];
+        let n = self.encode_u64_into(value, &mut buf)?;
+        write_all(writer, &buf[..n])
+    }
+}
+
+impl Default for Vlq {
+    /// No limit beyond the width of the integer type.
+    fn default() -> Self {
+        Vlq::new(MAX_LEN_U64)
+    }
+}
+
+/// Returns the number of bytes needed to encode `value` as a VLQ.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::encoded_len;
+///
+/// assert_eq!(encoded_len(0), 1);
+/// assert_eq!(encoded_len(127), 1);
+/// assert_eq!(encoded_len(128), 2);
+/// assert_eq!(encoded_len(0x0FFF_FFFF), 4);
+/// assert_eq!(encoded_len(u64::MAX), 10);
+/// ```
+pub fn encoded_len(value: u64) -> usize {
+    let bits = (u64::BITS - value.leading_zeros()).max(1) as usize;
+    bits.div_ceil(7)
+}
+
+fn encode_into(mut value: u64, max_len: usize, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    let len = encoded_len(value);
+    if len > max_len {
+        return Err(UVarintError::Overflow);
+    }
+    if buf.len() < len {
+        return Err(UVarintError::BufferTooSmall { needed: len });
+    }
+
+    // Fill from the least significant group backwards; only the last byte
+    // goes without a continuation bit.
+    for (i, byte) in buf[..len].iter_mut().enumerate().rev() {
+        *byte = (value & 0x7F) as u8;
+        if i + 1 < len {
+            *byte |= 0x80;
+        }
+        value >>= 7;
+    }
+
+    Ok(len)
+}
+
+fn decode(data: &[u8], max_len: usize, bits: u32) -> Result<(usize, u64), UVarintError> {
+    let mut value: u64 = 0;
+
+    for (i, &byte) in data.iter().take(max_len).enumerate() {
+        // Shifting in another group would push bits past the type's width
+        if value >> (bits - 7) != 0 {
+            return Err(UVarintError::Overflow);
+        }
+
+        value = (value << 7) | (byte & 0x7F) as u64;
+
+        if (byte & 0x80) == 0 {
+            return Ok((i + 1, value));
+        }
+    }
+
+    if data.len() >= max_len {
+        return Err(UVarintError::Overflow);
+    }
+
+    Err(UVarintError::Incomplete)
+}
+
+/// Reads bytes up to and including the first one without a continuation bit.
+fn read_raw<R: Read>(
+    reader: &mut R,
+    max_len: usize,
+) -> Result<([u8; MAX_LEN_U64], usize), UVarintError> {
+    let mut buf = [0u8; MAX_LEN_U64];
+
+    for i in 0..max_len {
+        reader
+            .read_exact(&mut buf[i..i + 1])
+            .map_err(|_| UVarintError::Incomplete)?;
+
+        if (buf[i] & 0x80) == 0 {
+            return Ok((buf, i + 1));
+        }
+    }
+
+    Err(UVarintError::Overflow)
+}
+
+fn write_all<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<usize, UVarintError> {
+    writer.write_all(bytes).map_err(UVarintError::WriteFailed)?;
+    Ok(bytes.len())
+}
+
+/// Encodes a u32 value as a VLQ.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::encode_u32;
+///
+/// assert_eq!(encode_u32(0x40), vec![0x40]);
+/// assert_eq!(encode_u32(300), vec![0x82, 0x2C]);
+/// assert_eq!(encode_u32(0x0FFF_FFFF), vec![0xFF, 0xFF, 0xFF, 0x7F]);
+/// ```
+pub fn encode_u32(value: u32) -> Vec<u8> {
+    let mut buf = [0u8; MAX_LEN_U32];
+    let n = encode_u32_into(value, &mut buf).expect("MAX_LEN_U32 fits any u32");
+    buf[..n].to_vec()
+}
+
+/// Encodes a u32 as a VLQ into a provided buffer, returning the number of bytes
+/// written.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::encode_u32_into;
+///
+/// let mut buf = [0u8; 5];
+/// let n = encode_u32_into(300, &mut buf).unwrap();
+/// assert_eq!(&buf[..n], &[0x82, 0x2C]);
+/// ```
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u32_into(value: u32, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    Vlq::default().encode_u32_into(value, buf)
+}
+
+/// Encodes a u64 value as a VLQ.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::encode_u64;
+///
+/// assert_eq!(encode_u64(300), vec![0x82, 0x2C]);
+/// ```
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    let mut buf = [0u8; MAX_LEN_U64];
+    let n = encode_u64_into(value, &mut buf).expect("MAX_LEN_U64 fits any u64");
+    buf[..n].to_vec()
+}
+
+/// Encodes a u64 as a VLQ into a provided buffer, returning the number of bytes
+/// written.
+///
+/// # Errors
+///
+/// Returns `UVarintError::BufferTooSmall` if the buffer is too small, leaving
+/// it untouched.
+pub fn encode_u64_into(value: u64, buf: &mut [u8]) -> Result<usize, UVarintError> {
+    Vlq::default().encode_u64_into(value, buf)
+}
+
+/// Decodes a VLQ-encoded u32 from a byte slice.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::decode_u32;
+///
+/// assert_eq!(decode_u32(&[0x82, 0x2C]).unwrap(), (2, 300));
+/// assert_eq!(decode_u32(&[0x81, 0x80, 0x80, 0x00]).unwrap(), (4, 0x0020_0000));
+/// ```
+pub fn decode_u32(data: &[u8]) -> Result<(usize, u32), UVarintError> {
+    Vlq::default().decode_u32(data)
+}
+
+/// Decodes a VLQ-encoded u64 from a byte slice.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::vlq::decode_u64;
+///
+/// assert_eq!(decode_u64(&[0x82, 0x2C]).unwrap(), (2, 300));
+/// ```
+pub fn decode_u64(data: &[u8]) -> Result<(usize, u64), UVarintError> {
+    Vlq::default().decode_u64(data)
+}
+
+/// Streaming VLQ helpers over `std::io`, with no length limit beyond the
+/// integer type; use the [`Vlq`] methods to enforce one.
+pub mod io {
+    use std::io::{Read, Write};
+
+    use super::Vlq;
+    use crate::error::UVarintError;
+
+    /// Read a VLQ-encoded u32 from any `Read` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use uvarint::vlq::io::read_u32;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x82, 0x2C]);
+    /// assert_eq!(read_u32(&mut cursor).unwrap(), 300);
+    /// ```
+    pub fn read_u32<R: Read>(reader: &mut R) -> Result<u32, UVarintError> {
+        Vlq::default().read_u32(reader)
+    }
+
+    /// Read a VLQ-encoded u64 from any `Read` implementation
+    pub fn read_u64<R: Read>(reader: &mut R) -> Result<u64, UVarintError> {
+        Vlq::default().read_u64(reader)
+    }
+
+    /// Write a VLQ-encoded u32 to any `Write` implementation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uvarint::vlq::io::write_u32;
+    ///
+    /// let mut buf = Vec::new();
+    /// write_u32(&mut buf, 300).unwrap();
+    /// assert_eq!(buf, vec![0x82, 0x2C]);
+    /// ```
+    pub fn write_u32<W: Write>(writer: &mut W, value: u32) -> Result<usize, UVarintError> {
+        Vlq::default().write_u32(writer, value)
+    }
+
+    /// Write a VLQ-encoded u64 to any `Write` implementation
+    pub fn write_u64<W: Write>(writer: &mut W, value: u64) -> Result<usize, UVarintError> {
+        Vlq::default().write_u64(writer, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::io::read_u32;
+    use super::*;
+    use std::io::Cursor;
+
+    /// Examples from the Standard MIDI File specification.
+    const MIDI_EXAMPLES: [(u32, &[u8]); 12] = [
+        (0x0000_0000, &[0x00]),
+        (0x0000_0040, &[0x40]),
+        (0x0000_007F, &[0x7F]),
+        (0x0000_0080, &[0x81, 0x00]),
+        (0x0000_2000, &[0xC0, 0x00]),
+        (0x0000_3FFF, &[0xFF, 0x7F]),
+        (0x0000_4000, &[0x81, 0x80, 0x00]),
+        (0x0010_0000, &[0xC0, 0x80, 0x00]),
+        (0x001F_FFFF, &[0xFF, 0xFF, 0x7F]),
+        (0x0020_0000, &[0x81, 0x80, 0x80, 0x00]),
+        (0x0800_0000, &[0xC0, 0x80, 0x80, 0x00]),
+        (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+    ];
+
+    #[test]
+    fn test_midi_spec_examples() {
+        for (value, bytes) in MIDI_EXAMPLES {
+            assert_eq!(encode_u32(value), bytes);
+            assert_eq!(Vlq::MIDI.decode_u32(bytes).unwrap(), (bytes.len(), value));
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_max_values() {
+        assert_eq!(encode_u32(u32::MAX), vec![0x8F, 0xFF, 0xFF, 0xFF, 0x7F]);
+        assert_eq!(decode_u32(&encode_u32(u32::MAX)).unwrap(), (5, u32::MAX));
+        assert_eq!(decode_u64(&encode_u64(u64::MAX)).unwrap(), (10, u64::MAX));
+    }
+
+    #[test]
+    fn test_decode_overflow() {
+        // Fits in 5 bytes, but needs 35 bits
+        assert!(matches!(
+            decode_u32(&[0x90, 0x80, 0x80, 0x80, 0x00]),
+            Err(UVarintError::Overflow)
+        ));
+        assert!(matches!(
+            decode_u32(&[0x80; 6]),
+            Err(UVarintError::Overflow)
+        ));
+        assert!(matches!(
+            decode_u64(&[0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(UVarintError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(matches!(decode_u32(&[]), Err(UVarintError::Incomplete)));
+        assert!(matches!(
+            Vlq::MIDI.decode_u32(&[0x81, 0x80]),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_midi_limit() {
+        let mut buf = [0u8; 5];
+        assert!(matches!(
+            Vlq::MIDI.encode_u32_into(0x1000_0000, &mut buf),
+            Err(UVarintError::Overflow)
+        ));
+        assert!(matches!(
+            Vlq::MIDI.decode_u32(&[0x81, 0x80, 0x80, 0x80, 0x00]),
+            Err(UVarintError::Overflow)
+        ));
+
+        // Unlimited codec accepts the same bytes
+        assert_eq!(
+            decode_u32(&[0x81, 0x80, 0x80, 0x80, 0x00]).unwrap(),
+            (5, 0x1000_0000)
+        );
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf = [0u8; 1];
+        assert!(matches!(
+            encode_u32_into(300, &mut buf),
+            Err(UVarintError::BufferTooSmall { needed: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_read_write_roundtrip() {
+        let mut buf = Vec::new();
+        for (value, _) in MIDI_EXAMPLES {
+            Vlq::MIDI.write_u32(&mut buf, value).unwrap();
+        }
+
+        let mut cursor = Cursor::new(buf);
+        for (value, _) in MIDI_EXAMPLES {
+            assert_eq!(Vlq::MIDI.read_u32(&mut cursor).unwrap(), value);
+        }
+        assert!(matches!(
+            read_u32(&mut cursor),
+            Err(UVarintError::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn test_read_midi_limit() {
+        let mut cursor = Cursor::new(vec![0x81, 0x80, 0x80, 0x80, 0x00]);
+        assert!(matches!(
+            Vlq::MIDI.read_u32(&mut cursor),
+            Err(UVarintError::Overflow)
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+/// Maps a signed i32 onto a u32 so that values of small magnitude stay small.
+///
+/// # ZigZag Encoding
+///
+/// Two's complement puts small negative numbers at the top of the unsigned
+/// range (`-1 = 0xFFFF_FFFF`), which would always take the maximum number of
+/// varint bytes. ZigZag interleaves the signs instead:
+///
+/// ```text
+///  0 → 0
+/// -1 → 1
+///  1 → 2
+/// -2 → 3
+///  2 → 4
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_encode_i32;
+///
+/// assert_eq!(zigzag_encode_i32(0), 0);
+/// assert_eq!(zigzag_encode_i32(-1), 1);
+/// assert_eq!(zigzag_encode_i32(1), 2);
+/// assert_eq!(zigzag_encode_i32(i32::MIN), u32::MAX);
+/// ```
+pub const fn zigzag_encode_i32(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+/// Reverses [`zigzag_encode_i32`].
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_decode_i32;
+///
+/// assert_eq!(zigzag_decode_i32(3), -2);
+/// assert_eq!(zigzag_decode_i32(4), 2);
+/// ```
+pub const fn zigzag_decode_i32(value: u32) -> i32 {
+    ((value >> 1) as i32) ^ -((value & 1) as i32)
+}
+
+/// Maps a signed i64 onto a u64 so that values of small magnitude stay small.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_encode_i64;
+///
+/// assert_eq!(zigzag_encode_i64(-64), 127);
+/// assert_eq!(zigzag_encode_i64(64), 128);
+/// assert_eq!(zigzag_encode_i64(i64::MIN), u64::MAX);
+/// ```
+pub const fn zigzag_encode_i64(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverses [`zigzag_encode_i64`].
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_decode_i64;
+///
+/// assert_eq!(zigzag_decode_i64(127), -64);
+/// assert_eq!(zigzag_decode_i64(u64::MAX), i64::MIN);
+/// ```
+pub const fn zigzag_decode_i64(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Maps a signed i128 onto a u128 so that values of small magnitude stay small.
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_encode_i128;
+///
+/// assert_eq!(zigzag_encode_i128(-1), 1);
+/// assert_eq!(zigzag_encode_i128(i128::MIN), u128::MAX);
+/// ```
+pub const fn zigzag_encode_i128(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+/// Reverses [`zigzag_encode_i128`].
+///
+/// # Examples
+///
+/// ```
+/// use uvarint::zigzag_decode_i128;
+///
+/// assert_eq!(zigzag_decode_i128(2), 1);
+/// assert_eq!(zigzag_decode_i128(u128::MAX), i128::MIN);
+/// ```
+pub const fn zigzag_decode_i128(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zigzag_i32_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 64, i32::MAX, i32::MIN] {
+            assert_eq!(zigzag_decode_i32(zigzag_encode_i32(value)), value);
+        }
+        assert_eq!(zigzag_encode_i32(i32::MAX), u32::MAX - 1);
+    }
+
+    #[test]
+    fn test_zigzag_i64_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 64, i64::MAX, i64::MIN] {
+            assert_eq!(zigzag_decode_i64(zigzag_encode_i64(value)), value);
+        }
+        assert_eq!(zigzag_encode_i64(i64::MAX), u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_zigzag_i128_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 64, i128::MAX, i128::MIN] {
+            assert_eq!(zigzag_decode_i128(zigzag_encode_i128(value)), value);
+        }
+        assert_eq!(zigzag_encode_i128(i128::MAX), u128::MAX - 1);
+    }
+}
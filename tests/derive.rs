@@ -0,0 +1,177 @@
+#![cfg(feature = "derive")]
+
+use uvarint::{UVarintError, VarintDecode, VarintEncode};
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+struct Header {
+    version: u8,
+    #[varint(fixed)]
+    magic: u32,
+}
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+struct Record {
+    header: Header,
+    id: u64,
+    #[varint(zigzag)]
+    delta: i64,
+    name: String,
+    tags: Vec<String>,
+    parent: Option<u32>,
+    #[varint(skip)]
+    cache: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+struct Pair(u32, #[varint(zigzag)] i32);
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+struct Marker;
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+enum Shape {
+    Empty,
+    Circle(#[varint(fixed)] u16),
+    Rect {
+        w: u32,
+        #[varint(zigzag)]
+        h: i32,
+    },
+}
+
+#[derive(Debug, PartialEq, VarintEncode, VarintDecode)]
+struct Wrapper<T> {
+    items: Vec<T>,
+}
+
+fn roundtrip<T: VarintEncode + VarintDecode + PartialEq + std::fmt::Debug>(value: T) -> Vec<u8> {
+    let encoded = value.encode();
+    assert_eq!(encoded.len(), value.encoded_len());
+    assert_eq!(T::decode(&encoded).unwrap(), (encoded.len(), value));
+    encoded
+}
+
+#[test]
+fn test_struct_roundtrip() {
+    roundtrip(Record {
+        header: Header {
+            version: 1,
+            magic: 0xCAFE_F00D,
+        },
+        id: u64::MAX,
+        delta: -5,
+        name: "record".to_string(),
+        tags: vec!["a".to_string(), "bc".to_string()],
+        parent: Some(300),
+        cache: Vec::new(),
+    });
+}
+
+#[test]
+fn test_skipped_field_is_not_encoded() {
+    let record = Record {
+        header: Header {
+            version: 0,
+            magic: 0,
+        },
+        id: 0,
+        delta: 0,
+        name: String::new(),
+        tags: Vec::new(),
+        parent: None,
+        cache: vec![1, 2, 3],
+    };
+
+    let encoded = record.encode();
+    assert_eq!(encoded, [0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+    let (_, decoded) = Record::decode(&encoded).unwrap();
+    assert!(decoded.cache.is_empty());
+}
+
+#[test]
+fn test_tuple_and_unit_structs() {
+    assert_eq!(roundtrip(Pair(300, -1)), [0xAC, 0x02, 0x01]);
+    assert!(roundtrip(Marker).is_empty());
+}
+
+#[test]
+fn test_enum_roundtrip() {
+    assert_eq!(roundtrip(Shape::Empty), [0x00]);
+    assert_eq!(roundtrip(Shape::Circle(0x0102)), [0x01, 0x02, 0x01]);
+    assert_eq!(roundtrip(Shape::Rect { w: 3, h: -2 }), [0x02, 0x03, 0x03]);
+
+    assert!(matches!(
+        Shape::decode(&[0x03]),
+        Err(UVarintError::InvalidVariant(3))
+    ));
+}
+
+#[test]
+fn test_min_encoded_len() {
+    assert_eq!(Marker::MIN_ENCODED_LEN, 0);
+    assert_eq!(Header::MIN_ENCODED_LEN, 5);
+    assert_eq!(Pair::MIN_ENCODED_LEN, 2);
+    assert_eq!(Shape::MIN_ENCODED_LEN, 1);
+    // Skipped fields take no bytes
+    assert_eq!(Record::MIN_ENCODED_LEN, 5 + 5);
+}
+
+#[test]
+fn test_vec_of_unit_structs_rejects_hostile_count() {
+    let hostile = uvarint::encode_u64(u64::MAX);
+    assert!(matches!(
+        Vec::<Marker>::decode(&hostile),
+        Err(UVarintError::SequenceTooLong {
+            count: u64::MAX,
+            ..
+        })
+    ));
+
+    // Non-empty elements cannot outnumber the bytes left
+    let mut data = uvarint::encode_u64(1 << 20);
+    data.extend([0x00; 8]);
+    assert!(matches!(
+        Vec::<Pair>::decode(&data),
+        Err(UVarintError::Incomplete)
+    ));
+
+    assert_eq!(roundtrip(vec![Marker, Marker, Marker]), [0x03]);
+}
+
+#[test]
+fn test_generic_struct() {
+    roundtrip(Wrapper {
+        items: vec![Pair(1, -1), Pair(2, 2)],
+    });
+}
+
+#[test]
+fn test_error_names_failing_field() {
+    let pair = Pair(300, -1).encode();
+    let err = Pair::decode(&pair[..2]).unwrap_err();
+    assert!(matches!(err, UVarintError::Field { field: "1", .. }));
+
+    let err = Shape::decode(&[0x02, 0x03]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid field `Rect.h`: Incomplete varint data"
+    );
+
+    // Nested structs report the whole path
+    let err = Record::decode(&[0x01, 0xAA]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid field `header`: Invalid field `magic`: Incomplete varint data"
+    );
+}
+
+#[test]
+fn test_encode_into_buffer_too_small() {
+    let mut buf = [0xAAu8; 2];
+    assert!(matches!(
+        Pair(300, -1).encode_into(&mut buf),
+        Err(UVarintError::BufferTooSmall { needed: 3 })
+    ));
+    assert_eq!(buf, [0xAA; 2]);
+}
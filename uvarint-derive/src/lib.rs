@@ -0,0 +1,382 @@
+//! Derive macros for `uvarint`'s `VarintEncode` and `VarintDecode` traits.
+//!
+//! Use these through `uvarint` with the `derive` feature enabled rather than
+//! depending on this crate directly. The format and the `#[varint(...)]` field
+//! attributes are documented on `uvarint::record`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, GenericParam, Generics, Ident, Index, Member,
+    Type, parse_macro_input, parse_quote,
+};
+
+/// Derives `uvarint::VarintEncode`, encoding fields in declaration order.
+///
+/// Fields take an optional `#[varint(...)]` attribute:
+///
+/// - none: the field type's own impl. Signed integers are written as their
+///   two's complement bits, so negative values take the maximum length and
+///   the output differs from `uvarint`'s ZigZag-based `serde` format.
+/// - `zigzag`: ZigZag-encode a signed integer, the encoding `serde`,
+///   `SliceWriter` and the `bytes` extension traits use for signed values.
+/// - `fixed`: little-endian bytes of the integer's full width.
+/// - `skip`: leave the field out; `VarintDecode` fills it with `Default`.
+#[proc_macro_derive(VarintEncode, attributes(varint))]
+pub fn derive_varint_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `uvarint::VarintDecode`, decoding fields in declaration order.
+#[proc_macro_derive(VarintDecode, attributes(varint))]
+pub fn derive_varint_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a single field is written, chosen by its `#[varint(...)]` attribute.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Plain,
+    ZigZag,
+    Fixed,
+    Skip,
+}
+
+struct Field {
+    /// Name reported in `UVarintError::Field`, e.g. `id` or `Rect.w`.
+    label: String,
+    member: Member,
+    binding: Ident,
+    ty: Type,
+    kind: Kind,
+}
+
+fn parse_kind(attrs: &[Attribute]) -> syn::Result<Kind> {
+    let mut kind = Kind::Plain;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("varint")) {
+        attr.parse_nested_meta(|meta| {
+            let next = if meta.path.is_ident("zigzag") {
+                Kind::ZigZag
+            } else if meta.path.is_ident("fixed") {
+                Kind::Fixed
+            } else if meta.path.is_ident("skip") {
+                Kind::Skip
+            } else {
+                return Err(meta.error("expected `zigzag`, `fixed` or `skip`"));
+            };
+
+            if kind != Kind::Plain {
+                return Err(meta.error("only one `varint` attribute is allowed per field"));
+            }
+            kind = next;
+            Ok(())
+        })?;
+    }
+
+    Ok(kind)
+}
+
+fn parse_fields(fields: &Fields, prefix: &str) -> syn::Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let (member, name) = match &field.ident {
+                Some(ident) => (Member::Named(ident.clone()), ident.to_string()),
+                None => (Member::Unnamed(Index::from(i)), i.to_string()),
+            };
+
+            Ok(Field {
+                label: format!("{prefix}{name}"),
+                member,
+                binding: format_ident!("__field{}", i),
+                ty: field.ty.clone(),
+                kind: parse_kind(&field.attrs)?,
+            })
+        })
+        .collect()
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(#bound));
+        }
+    }
+    generics
+}
+
+/// Wraps a field for encoding. `reference` is a `&T` expression and `value`
+/// the place it points to.
+fn encodable(field: &Field, reference: TokenStream2, value: TokenStream2) -> TokenStream2 {
+    match field.kind {
+        Kind::Plain => reference,
+        Kind::ZigZag => quote!(&::uvarint::record::ZigZag(#value)),
+        Kind::Fixed => quote!(&::uvarint::record::Fixed(#value)),
+        Kind::Skip => unreachable!("skipped fields are never encoded"),
+    }
+}
+
+/// Returns `(encoded_len terms, encode_into statements)` for `fields`.
+fn encode_fields(
+    fields: &[Field],
+    access: impl Fn(&Field) -> (TokenStream2, TokenStream2),
+) -> (Vec<TokenStream2>, Vec<TokenStream2>) {
+    fields
+        .iter()
+        .filter(|field| field.kind != Kind::Skip)
+        .map(|field| {
+            let (reference, value) = access(field);
+            let encodable = encodable(field, reference, value);
+            (
+                quote!(::uvarint::VarintEncode::encoded_len(#encodable)),
+                quote! {
+                    let pos = pos + ::uvarint::VarintEncode::encode_into(#encodable, &mut buf[pos..])?;
+                },
+            )
+        })
+        .unzip()
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::uvarint::VarintEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (len_body, encode_body) = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, "")?;
+            let (lens, encodes) = encode_fields(&fields, |field| {
+                let member = &field.member;
+                (quote!(&self.#member), quote!(self.#member))
+            });
+
+            (
+                quote!(0 #(+ #lens)*),
+                quote! {
+                    let pos = 0;
+                    #(#encodes)*
+                    ::core::result::Result::Ok(pos)
+                },
+            )
+        }
+        Data::Enum(data) if data.variants.is_empty() => {
+            (quote!(match *self {}), quote!(match *self {}))
+        }
+        Data::Enum(data) => {
+            let mut len_arms = Vec::new();
+            let mut encode_arms = Vec::new();
+
+            for (index, variant) in data.variants.iter().enumerate() {
+                let index = index as u64;
+                let ident = &variant.ident;
+                let fields = parse_fields(&variant.fields, &format!("{ident}."))?;
+                let pattern = variant_pattern(ident, &fields);
+                let (lens, encodes) = encode_fields(&fields, |field| {
+                    let binding = &field.binding;
+                    (quote!(#binding), quote!(*#binding))
+                });
+
+                len_arms.push(quote! {
+                    #pattern => ::uvarint::encoded_len_u64(#index) #(+ #lens)*,
+                });
+                encode_arms.push(quote! {
+                    #pattern => {
+                        let pos = ::uvarint::encode_u64_into(#index, buf)?;
+                        #(#encodes)*
+                        ::core::result::Result::Ok(pos)
+                    }
+                });
+            }
+
+            (
+                quote!(match self { #(#len_arms)* }),
+                quote!(match self { #(#encode_arms)* }),
+            )
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "VarintEncode cannot be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::uvarint::VarintEncode for #name #ty_generics #where_clause {
+            fn encoded_len(&self) -> usize {
+                #len_body
+            }
+
+            #[allow(unused_variables)]
+            fn encode_into(&self, buf: &mut [u8]) -> ::core::result::Result<usize, ::uvarint::UVarintError> {
+                // Size check up front, so a failed encode writes nothing
+                let len = ::uvarint::VarintEncode::encoded_len(self);
+                let buf = buf
+                    .get_mut(..len)
+                    .ok_or(::uvarint::UVarintError::BufferTooSmall { needed: len })?;
+                #encode_body
+            }
+        }
+    })
+}
+
+/// Builds `Self::Variant { 0: __field0, name: __field1, skipped: _ }`, which
+/// matches unit, tuple and struct variants alike.
+fn variant_pattern(ident: &Ident, fields: &[Field]) -> TokenStream2 {
+    let bindings = fields.iter().map(|field| {
+        let member = &field.member;
+        let binding = &field.binding;
+        if field.kind == Kind::Skip {
+            quote!(#member: _)
+        } else {
+            quote!(#member: #binding)
+        }
+    });
+
+    quote!(Self::#ident { #(#bindings),* })
+}
+
+/// Decodes each field into its `__fieldN` binding, advancing `pos`.
+fn decode_fields(fields: &[Field]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| {
+            let Field {
+                label,
+                binding,
+                ty,
+                kind,
+                ..
+            } = field;
+
+            let decode = match kind {
+                Kind::Skip => {
+                    return quote! {
+                        let #binding: #ty = ::core::default::Default::default();
+                    };
+                }
+                Kind::Plain => quote! {
+                    let (n, #binding) = <#ty as ::uvarint::VarintDecode>::decode(&data[pos..])
+                },
+                Kind::ZigZag => quote! {
+                    let (n, ::uvarint::record::ZigZag(#binding)) =
+                        <::uvarint::record::ZigZag<#ty> as ::uvarint::VarintDecode>::decode(&data[pos..])
+                },
+                Kind::Fixed => quote! {
+                    let (n, ::uvarint::record::Fixed(#binding)) =
+                        <::uvarint::record::Fixed<#ty> as ::uvarint::VarintDecode>::decode(&data[pos..])
+                },
+            };
+
+            quote! {
+                #decode.map_err(|e| ::uvarint::UVarintError::Field {
+                    field: #label,
+                    source: ::std::boxed::Box::new(e),
+                })?;
+                let pos = pos + n;
+            }
+        })
+        .collect()
+}
+
+/// Sums the `MIN_ENCODED_LEN` of every field that is actually encoded.
+fn min_encoded_len(fields: &[Field]) -> TokenStream2 {
+    let lens = fields.iter().filter_map(|field| {
+        let ty = &field.ty;
+        let ty = match field.kind {
+            Kind::Plain => quote!(#ty),
+            Kind::ZigZag => quote!(::uvarint::record::ZigZag<#ty>),
+            Kind::Fixed => quote!(::uvarint::record::Fixed<#ty>),
+            Kind::Skip => return None,
+        };
+        Some(quote!(<#ty as ::uvarint::VarintDecode>::MIN_ENCODED_LEN))
+    });
+
+    quote!(0 #(+ #lens)*)
+}
+
+fn construct(path: TokenStream2, fields: &[Field]) -> TokenStream2 {
+    let inits = fields.iter().map(|field| {
+        let member = &field.member;
+        let binding = &field.binding;
+        quote!(#member: #binding)
+    });
+
+    quote!(#path { #(#inits),* })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::uvarint::VarintDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (min_len, body) = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, "")?;
+            let decodes = decode_fields(&fields);
+            let value = construct(quote!(Self), &fields);
+
+            let body = quote! {
+                let pos = 0;
+                #(#decodes)*
+                ::core::result::Result::Ok((pos, #value))
+            };
+            (min_encoded_len(&fields), body)
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+
+            for (index, variant) in data.variants.iter().enumerate() {
+                let index = index as u64;
+                let ident = &variant.ident;
+                let fields = parse_fields(&variant.fields, &format!("{ident}."))?;
+                let decodes = decode_fields(&fields);
+                let value = construct(quote!(Self::#ident), &fields);
+
+                arms.push(quote! {
+                    #index => {
+                        #(#decodes)*
+                        ::core::result::Result::Ok((pos, #value))
+                    }
+                });
+            }
+
+            let body = quote! {
+                let (pos, index) = ::uvarint::decode_u64(data)?;
+                match index {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(::uvarint::UVarintError::InvalidVariant(index)),
+                }
+            };
+            // The variant index alone takes a byte
+            (quote!(1), body)
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "VarintDecode cannot be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::uvarint::VarintDecode for #name #ty_generics #where_clause {
+            const MIN_ENCODED_LEN: usize = #min_len;
+
+            #[allow(unused_variables)]
+            fn decode(data: &[u8]) -> ::core::result::Result<(usize, Self), ::uvarint::UVarintError> {
+                #body
+            }
+        }
+    })
+}